@@ -216,6 +216,31 @@ impl AccountCommands {
             AccountCommands::Display { name } => {
                 client.display_principal(&name).await;
             }
+            AccountCommands::ExportSubscriptions { name } => {
+                for mailbox in client
+                    .http_request::<Vec<String>, String>(
+                        Method::GET,
+                        &format!("/api/store/subscriptions/{name}"),
+                        None,
+                    )
+                    .await
+                {
+                    println!("{mailbox}");
+                }
+            }
+            AccountCommands::ImportSubscriptions { name, mailboxes } => {
+                let not_found = client
+                    .http_request::<Vec<String>, _>(
+                        Method::POST,
+                        &format!("/api/store/subscriptions/{name}"),
+                        Some(mailboxes),
+                    )
+                    .await;
+                for mailbox in &not_found {
+                    eprintln!("Mailbox {mailbox:?} not found.");
+                }
+                eprintln!("Successfully updated subscriptions of account {name:?}.");
+            }
             AccountCommands::List {
                 filter,
                 limit,
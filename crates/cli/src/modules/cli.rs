@@ -171,6 +171,21 @@ pub enum AccountCommands {
         name: String,
     },
 
+    /// Export the mailboxes a user account is subscribed to
+    ExportSubscriptions {
+        /// Account login
+        name: String,
+    },
+
+    /// Subscribe a user account to mailboxes
+    ImportSubscriptions {
+        /// Account login
+        name: String,
+        /// Mailbox names to subscribe to
+        #[clap(required = true)]
+        mailboxes: Vec<String>,
+    },
+
     /// List all user accounts
     List {
         /// Filter accounts by keywords
@@ -398,6 +413,12 @@ pub enum ServerCommands {
     /// Perform database maintenance
     DatabaseMaintenance {},
 
+    /// Rewrite FoundationDB values chunked in the previous layout
+    PurgeChunks {
+        /// Store id, defaults to the data store
+        store: Option<String>,
+    },
+
     /// Reload TLS certificates
     ReloadCertificates {},
 
@@ -23,6 +23,17 @@ impl ServerCommands {
                     .await;
                 eprintln!("Success.");
             }
+            ServerCommands::PurgeChunks { store } => {
+                let url = if let Some(store) = store {
+                    format!("/api/store/purge/chunks/{store}")
+                } else {
+                    "/api/store/purge/chunks".to_string()
+                };
+                client
+                    .http_request::<Value, String>(Method::GET, &url, None)
+                    .await;
+                eprintln!("Success.");
+            }
             ServerCommands::ReloadCertificates {} => {
                 client
                     .http_request::<Value, String>(Method::GET, "/api/reload/certificate", None)
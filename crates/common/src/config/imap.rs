@@ -6,13 +6,20 @@
 
 use std::time::Duration;
 
+use ahash::AHashMap;
 use utils::config::{Config, Rate};
 
 #[derive(Default, Clone)]
 pub struct ImapConfig {
     pub max_request_size: usize,
+    pub literal_minus: bool,
     pub max_auth_failures: u32,
     pub allow_plain_auth: bool,
+    pub folders_case_insensitive: bool,
+    pub folder_roles: AHashMap<String, &'static str>,
+
+    pub id_vendor: Option<String>,
+    pub id_support_url: Option<String>,
 
     pub timeout_auth: Duration,
     pub timeout_unauth: Duration,
@@ -28,6 +35,9 @@ impl ImapConfig {
             max_request_size: config
                 .property_or_default("imap.request.max-size", "52428800")
                 .unwrap_or(52428800),
+            literal_minus: config
+                .property_or_default("imap.request.literal-minus", "false")
+                .unwrap_or(false),
             max_auth_failures: config
                 .property_or_default("imap.auth.max-failures", "3")
                 .unwrap_or(3),
@@ -49,6 +59,115 @@ impl ImapConfig {
             allow_plain_auth: config
                 .property_or_default("imap.auth.allow-plain-text", "false")
                 .unwrap_or(false),
+            folders_case_insensitive: config
+                .property_or_default("imap.folders.case-insensitive", "false")
+                .unwrap_or(false),
+            id_vendor: config
+                .property_or_default::<Option<String>>("imap.id.vendor", "Stalwart Labs Ltd.")
+                .unwrap_or_default(),
+            id_support_url: config
+                .property_or_default::<Option<String>>("imap.id.support-url", "https://stalw.art")
+                .unwrap_or_default(),
+            folder_roles: if config
+                .property_or_default("imap.folders.infer-role.enable", "false")
+                .unwrap_or(false)
+            {
+                parse_folder_roles(config)
+            } else {
+                AHashMap::new()
+            },
         }
     }
 }
+
+static DEFAULT_FOLDER_ROLES: &[(&str, &str, &[&str])] = &[
+    ("en", "archive", &["Archive", "Archives"]),
+    ("en", "drafts", &["Drafts", "Draft"]),
+    ("en", "junk", &["Junk", "Junk Mail", "Junk E-mail", "Spam"]),
+    (
+        "en",
+        "sent",
+        &["Sent", "Sent Items", "Sent Messages", "Sent Mail"],
+    ),
+    (
+        "en",
+        "trash",
+        &["Trash", "Deleted Items", "Deleted Messages", "Bin"],
+    ),
+    ("de", "archive", &["Archiv"]),
+    ("de", "drafts", &["Entwürfe"]),
+    ("de", "junk", &["Junk-E-Mail", "Spam"]),
+    (
+        "de",
+        "sent",
+        &["Gesendet", "Gesendete Elemente", "Gesendete Objekte"],
+    ),
+    (
+        "de",
+        "trash",
+        &["Papierkorb", "Gelöschte Elemente", "Gelöschte Objekte"],
+    ),
+    ("es", "archive", &["Archivo"]),
+    ("es", "drafts", &["Borradores"]),
+    ("es", "junk", &["Correo no deseado"]),
+    ("es", "sent", &["Enviados", "Elementos enviados"]),
+    ("es", "trash", &["Papelera", "Elementos eliminados"]),
+    ("fr", "archive", &["Archives"]),
+    ("fr", "drafts", &["Brouillons"]),
+    ("fr", "junk", &["Courrier indésirable", "Indésirables"]),
+    (
+        "fr",
+        "sent",
+        &["Envoyés", "Éléments envoyés", "Messages envoyés"],
+    ),
+    ("fr", "trash", &["Corbeille", "Éléments supprimés"]),
+];
+
+fn parse_folder_roles(config: &mut Config) -> AHashMap<String, &'static str> {
+    let mut folder_roles = AHashMap::new();
+
+    // Built-in names, unless replaced for that locale and role
+    for (locale, role, names) in DEFAULT_FOLDER_ROLES {
+        if !config.has_prefix(("imap.folders.infer-role", *locale, *role)) {
+            for name in *names {
+                folder_roles.insert(name.to_lowercase(), *role);
+            }
+        }
+    }
+
+    // Names configured per locale
+    for locale in config
+        .sub_keys("imap.folders.infer-role", "")
+        .filter(|locale| *locale != "enable")
+        .map(|locale| locale.to_string())
+        .collect::<Vec<_>>()
+    {
+        for role in config
+            .sub_keys(("imap.folders.infer-role", locale.as_str()), "")
+            .map(|role| role.to_string())
+            .collect::<Vec<_>>()
+        {
+            let key = ("imap.folders.infer-role", locale.as_str(), role.as_str());
+            let role = match role.as_str() {
+                "archive" => "archive",
+                "drafts" => "drafts",
+                "junk" => "junk",
+                "sent" => "sent",
+                "trash" => "trash",
+                "important" => "important",
+                _ => {
+                    config.new_parse_error(key, format!("Invalid special use role {role:?}"));
+                    continue;
+                }
+            };
+            for (_, name) in config.values(key) {
+                let name = name.trim();
+                if !name.is_empty() {
+                    folder_roles.insert(name.to_lowercase(), role);
+                }
+            }
+        }
+    }
+
+    folder_roles
+}
@@ -72,6 +72,14 @@ pub enum Command {
 
     // RFC 2971
     Id,
+
+    // RFC 4978
+    Compress,
+
+    // RFC 9208
+    GetQuota,
+    GetQuotaRoot,
+    SetQuota,
 }
 
 impl Command {
@@ -128,6 +136,7 @@ pub enum ResponseCode {
     ReadOnly,
     ReadWrite,
     ServerBug,
+    TooBig,
     TryCreate,
     UidNext,
     UidNotSticky,
@@ -150,6 +159,9 @@ pub enum ResponseCode {
 
     // USEATTR
     UseAttr,
+
+    // COMPRESS
+    CompressionActive,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -323,7 +323,9 @@ mod tests {
                     );
                 }
                 Err(err) => match err {
-                    Error::NeedsMoreData | Error::NeedsLiteral { .. } => (),
+                    Error::NeedsMoreData
+                    | Error::NeedsLiteral { .. }
+                    | Error::NonSyncLiteral { .. } => (),
                     Error::Error { response } => panic!("{:?}", response),
                 },
             }
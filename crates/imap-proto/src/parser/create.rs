@@ -7,22 +7,23 @@
 use crate::{
     protocol::{create, ProtocolVersion},
     receiver::{bad, Request, Token},
-    utf7::utf7_maybe_decode,
-    Command,
+    utf7::utf7_strict_decode,
+    Command, ResponseCode,
 };
 
 impl Request<Command> {
     pub fn parse_create(self, version: ProtocolVersion) -> trc::Result<create::Arguments> {
         if !self.tokens.is_empty() {
             let mut tokens = self.tokens.into_iter();
-            let mailbox_name = utf7_maybe_decode(
+            let mailbox_name = utf7_strict_decode(
                 tokens
                     .next()
                     .unwrap()
                     .unwrap_string()
                     .map_err(|v| bad(self.tag.clone(), v))?,
                 version,
-            );
+            )
+            .ok_or_else(|| bad(self.tag.clone(), "Invalid modified UTF-7 mailbox name."))?;
             let mailbox_role = if let Some(Token::ParenthesisOpen) = tokens.next() {
                 match tokens.next() {
                     Some(Token::Argument(param)) if param.eq_ignore_ascii_case(b"USE") => (),
@@ -36,39 +37,55 @@ impl Request<Command> {
                 {
                     return Err(bad(self.tag, "Expected '(' after 'USE'."));
                 }
-                match tokens.next() {
-                    Some(Token::Argument(value)) => {
-                        Some(if value.eq_ignore_ascii_case(b"\\Archive") {
-                            "archive"
-                        } else if value.eq_ignore_ascii_case(b"\\Drafts") {
-                            "drafts"
-                        } else if value.eq_ignore_ascii_case(b"\\Junk") {
-                            "junk"
-                        } else if value.eq_ignore_ascii_case(b"\\Sent") {
-                            "sent"
-                        } else if value.eq_ignore_ascii_case(b"\\Trash") {
-                            "trash"
-                        } else if value.eq_ignore_ascii_case(b"\\Important") {
-                            "important"
-                        } else if value.eq_ignore_ascii_case(b"\\All") {
-                            return Err(bad(
-                                self.tag,
-                                "A mailbox with the \"\\All\" attribute already exists.",
-                            ));
-                        } else {
-                            return Err(bad(
-                                self.tag,
-                                format!(
-                                    "Special use attribute {:?} is not supported.",
-                                    String::from_utf8_lossy(&value)
-                                ),
-                            ));
-                        })
-                    }
-                    _ => {
-                        return Err(bad(self.tag, "Invalid SPECIAL-USE attribute."));
+                let mut mailbox_role = None;
+                loop {
+                    match tokens.next() {
+                        Some(Token::Argument(value)) => {
+                            let role = if value.eq_ignore_ascii_case(b"\\Archive") {
+                                "archive"
+                            } else if value.eq_ignore_ascii_case(b"\\Drafts") {
+                                "drafts"
+                            } else if value.eq_ignore_ascii_case(b"\\Junk") {
+                                "junk"
+                            } else if value.eq_ignore_ascii_case(b"\\Sent") {
+                                "sent"
+                            } else if value.eq_ignore_ascii_case(b"\\Trash") {
+                                "trash"
+                            } else if value.eq_ignore_ascii_case(b"\\Important") {
+                                "important"
+                            } else if value.eq_ignore_ascii_case(b"\\All") {
+                                return Err(bad(
+                                    self.tag,
+                                    "A mailbox with the \"\\All\" attribute already exists.",
+                                ));
+                            } else {
+                                return Err(bad(
+                                    self.tag,
+                                    format!(
+                                        "Special use attribute {:?} is not supported.",
+                                        String::from_utf8_lossy(&value)
+                                    ),
+                                ));
+                            };
+
+                            if mailbox_role.map_or(false, |mailbox_role| mailbox_role != role) {
+                                return Err(trc::ImapEvent::Error
+                                    .into_err()
+                                    .details(
+                                        "Only one special use attribute is supported per mailbox.",
+                                    )
+                                    .ctx(trc::Key::Id, self.tag)
+                                    .code(ResponseCode::UseAttr));
+                            }
+                            mailbox_role = Some(role);
+                        }
+                        Some(Token::ParenthesisClose) => break,
+                        _ => {
+                            return Err(bad(self.tag, "Invalid SPECIAL-USE attribute."));
+                        }
                     }
                 }
+                mailbox_role
             } else {
                 None
             };
@@ -121,6 +138,22 @@ mod tests {
                     mailbox_role: Some("important"),
                 },
             ),
+            (
+                "t1 CREATE \"Sent Items\" (USE (\\Sent \\Sent))\r\n",
+                create::Arguments {
+                    tag: "t1".to_string(),
+                    mailbox_name: "Sent Items".to_string(),
+                    mailbox_role: Some("sent"),
+                },
+            ),
+            (
+                "t1 CREATE \"Plain\" (USE ())\r\n",
+                create::Arguments {
+                    tag: "t1".to_string(),
+                    mailbox_name: "Plain".to_string(),
+                    mailbox_role: None,
+                },
+            ),
         ] {
             assert_eq!(
                 receiver
@@ -131,5 +164,71 @@ mod tests {
                 arguments
             );
         }
+
+        // Modified UTF-7 is only decoded for IMAP4rev1 clients
+        for (command, version, mailbox_name) in [
+            (
+                "t1 CREATE \"&ZeVnLIqe-\"\r\n",
+                ProtocolVersion::Rev1,
+                "日本語",
+            ),
+            (
+                "t1 CREATE \"&ZeVnLIqe-\"\r\n",
+                ProtocolVersion::Rev2,
+                "&ZeVnLIqe-",
+            ),
+            ("t1 CREATE \"日本語\"\r\n", ProtocolVersion::Rev2, "日本語"),
+        ] {
+            assert_eq!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_create(version)
+                    .unwrap()
+                    .mailbox_name,
+                mailbox_name
+            );
+        }
+
+        // Ill-formed names are rejected
+        for (command, version) in [
+            ("t1 CREATE \"日本語\"\r\n", ProtocolVersion::Rev1),
+            ("t1 CREATE \"&ZeVnLIqe\"\r\n", ProtocolVersion::Rev1),
+            ("t1 CREATE \"&AGE-\"\r\n", ProtocolVersion::Rev1),
+        ] {
+            assert!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_create(version)
+                    .is_err(),
+                "{command:?}"
+            );
+        }
+        let mut invalid_utf8 = b"t1 CREATE \"".to_vec();
+        invalid_utf8.extend_from_slice(&[0xc3, 0x28]);
+        invalid_utf8.extend_from_slice(b"\"\r\n");
+        assert!(receiver
+            .parse(&mut invalid_utf8.iter())
+            .unwrap()
+            .parse_create(ProtocolVersion::Rev2)
+            .is_err());
+
+        for command in [
+            "t1 CREATE \"Both\" (USE (\\Sent \\Trash))\r\n",
+            "t1 CREATE \"Unknown\" (USE (\\Unknown))\r\n",
+            "t1 CREATE \"All\" (USE (\\All))\r\n",
+            "t1 CREATE \"NoList\" (USE \\Sent)\r\n",
+            "t1 CREATE \"NoUse\" (\\Sent)\r\n",
+        ] {
+            assert!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_create(ProtocolVersion::Rev2)
+                    .is_err(),
+                "{command:?}"
+            );
+        }
     }
 }
@@ -0,0 +1,131 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    protocol::id,
+    receiver::{bad, Request, Token},
+    Command,
+};
+
+// Limits from RFC 2971, section 3.3
+const MAX_PARAMETERS: usize = 30;
+const MAX_FIELD_LEN: usize = 30;
+const MAX_VALUE_LEN: usize = 1024;
+
+impl Request<Command> {
+    pub fn parse_id(self) -> trc::Result<id::Arguments> {
+        let mut parameters = Vec::new();
+        let mut tokens = self.tokens.into_iter();
+
+        match tokens.next() {
+            Some(Token::ParenthesisOpen) => loop {
+                let name = match tokens.next() {
+                    Some(Token::ParenthesisClose) => break,
+                    Some(Token::Argument(_)) if parameters.len() == MAX_PARAMETERS => {
+                        return Err(bad(self.tag, "Too many field-value pairs."))
+                    }
+                    Some(Token::Argument(name)) if name.len() > MAX_FIELD_LEN => {
+                        return Err(bad(self.tag, "Field name is too long."))
+                    }
+                    Some(Token::Argument(name)) => String::from_utf8(name)
+                        .map_err(|_| bad(self.tag.clone(), "Invalid UTF-8 in field name."))?,
+                    _ => return Err(bad(self.tag, "Expected field name.")),
+                };
+                let value = match tokens.next() {
+                    Some(Token::Nil) => None,
+                    Some(Token::Argument(value)) if value.eq_ignore_ascii_case(b"NIL") => None,
+                    Some(Token::Argument(value)) if value.len() > MAX_VALUE_LEN => {
+                        return Err(bad(self.tag, "Field value is too long."))
+                    }
+                    Some(Token::Argument(value)) => String::from_utf8(value)
+                        .map_err(|_| bad(self.tag.clone(), "Invalid UTF-8 in field value."))?
+                        .into(),
+                    _ => return Err(bad(self.tag, "Expected field value.")),
+                };
+                parameters.push((name, value));
+            },
+            Some(Token::Argument(value)) if value.eq_ignore_ascii_case(b"NIL") => (),
+            None => (),
+            Some(_) => return Err(bad(self.tag, "Expected parameter list or NIL.")),
+        }
+
+        if tokens.next().is_none() {
+            Ok(id::Arguments {
+                tag: self.tag,
+                parameters,
+            })
+        } else {
+            Err(bad(self.tag, "Too many arguments."))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{protocol::id, receiver::Receiver};
+
+    #[test]
+    fn parse_id() {
+        let mut receiver = Receiver::new();
+
+        for (command, arguments) in [
+            (
+                "a023 ID (\"name\" \"sodr\" \"version\" \"19.34\" \"vendor\" NIL)\r\n",
+                id::Arguments {
+                    tag: "a023".to_string(),
+                    parameters: vec![
+                        ("name".to_string(), "sodr".to_string().into()),
+                        ("version".to_string(), "19.34".to_string().into()),
+                        ("vendor".to_string(), None),
+                    ],
+                },
+            ),
+            (
+                "a024 ID NIL\r\n",
+                id::Arguments {
+                    tag: "a024".to_string(),
+                    parameters: vec![],
+                },
+            ),
+        ] {
+            assert_eq!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_id()
+                    .unwrap(),
+                arguments
+            );
+        }
+
+        // Lists beyond the limits of RFC 2971 are rejected
+        let pairs = |count: usize| {
+            (0..count)
+                .map(|i| format!("\"field{i}\" \"value\""))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        assert!(receiver
+            .parse(&mut format!("a025 ID ({})\r\n", pairs(30)).as_bytes().iter())
+            .unwrap()
+            .parse_id()
+            .is_ok());
+        for command in [
+            format!("a025 ID ({})\r\n", pairs(31)),
+            format!("a025 ID (\"{}\" \"value\")\r\n", "a".repeat(31)),
+            format!("a025 ID (\"name\" {{1025+}}\r\n{})\r\n", "a".repeat(1025)),
+        ] {
+            assert!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_id()
+                    .is_err(),
+                "{command}"
+            );
+        }
+    }
+}
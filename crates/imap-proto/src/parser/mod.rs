@@ -7,14 +7,17 @@
 pub mod acl;
 pub mod append;
 pub mod authenticate;
+pub mod compress;
 pub mod copy_move;
 pub mod create;
 pub mod delete;
 pub mod enable;
 pub mod fetch;
+pub mod id;
 pub mod list;
 pub mod login;
 pub mod lsub;
+pub mod quota;
 pub mod rename;
 pub mod search;
 pub mod select;
@@ -77,6 +80,10 @@ impl CommandParser for Command {
             b"MYRIGHTS" => Some(Command::MyRights),
             b"UNAUTHENTICATE" => Some(Command::Unauthenticate),
             b"ID" => Some(Command::Id),
+            b"COMPRESS" => Some(Command::Compress),
+            b"GETQUOTA" => Some(Command::GetQuota),
+            b"GETQUOTAROOT" => Some(Command::GetQuotaRoot),
+            b"SETQUOTA" => Some(Command::SetQuota),
             _ => None,
         }
     }
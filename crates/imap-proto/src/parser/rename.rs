@@ -7,7 +7,7 @@
 use crate::{
     protocol::{rename, ProtocolVersion},
     receiver::{bad, Request},
-    utf7::utf7_maybe_decode,
+    utf7::{utf7_maybe_decode, utf7_strict_decode},
     Command,
 };
 
@@ -16,23 +16,29 @@ impl Request<Command> {
         match self.tokens.len() {
             2 => {
                 let mut tokens = self.tokens.into_iter();
+                let mailbox_name = utf7_maybe_decode(
+                    tokens
+                        .next()
+                        .unwrap()
+                        .unwrap_string()
+                        .map_err(|v| bad(self.tag.clone(), v))?,
+                    version,
+                );
+
+                // The new name is created, it must be well-formed
+                let new_mailbox_name = utf7_strict_decode(
+                    tokens
+                        .next()
+                        .unwrap()
+                        .unwrap_string()
+                        .map_err(|v| bad(self.tag.clone(), v))?,
+                    version,
+                )
+                .ok_or_else(|| bad(self.tag.clone(), "Invalid modified UTF-7 mailbox name."))?;
+
                 Ok(rename::Arguments {
-                    mailbox_name: utf7_maybe_decode(
-                        tokens
-                            .next()
-                            .unwrap()
-                            .unwrap_string()
-                            .map_err(|v| bad(self.tag.clone(), v))?,
-                        version,
-                    ),
-                    new_mailbox_name: utf7_maybe_decode(
-                        tokens
-                            .next()
-                            .unwrap()
-                            .unwrap_string()
-                            .map_err(|v| bad(self.tag.clone(), v))?,
-                        version,
-                    ),
+                    mailbox_name,
+                    new_mailbox_name,
                     tag: self.tag,
                 })
             }
@@ -81,5 +87,33 @@ mod tests {
                 arguments
             );
         }
+
+        // Ill-formed new names are rejected, existing names are decoded leniently
+        for command in [
+            "A142 RENAME Private \"&ZeVnLIqe\"\r\n",
+            "A142 RENAME Private \"&AGE-\"\r\n",
+            "A142 RENAME Private \"日本語\"\r\n",
+        ] {
+            assert!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_rename(ProtocolVersion::Rev1)
+                    .is_err(),
+                "{command}"
+            );
+        }
+        assert_eq!(
+            receiver
+                .parse(&mut "A142 RENAME \"&ZeVnLIqe\" Private\r\n".as_bytes().iter())
+                .unwrap()
+                .parse_rename(ProtocolVersion::Rev1)
+                .unwrap(),
+            rename::Arguments {
+                mailbox_name: "日本語".to_string(),
+                new_mailbox_name: "Private".to_string(),
+                tag: "A142".to_string(),
+            }
+        );
     }
 }
@@ -41,12 +41,16 @@ pub enum Capability {
     Move,
     CondStore,
     QResync,
-    LiteralPlus, //LITERAL+
+    LiteralPlus,  //LITERAL+
+    LiteralMinus, //LITERAL-
     UnAuthenticate,
     StatusSize, //STATUS=SIZE
     ObjectId,
     Preview,
     Utf8Accept,
+    CompressDeflate, //COMPRESS=DEFLATE
+    Quota,
+    QuotaResStorage, //QUOTA=RES-STORAGE
     Auth(Mechanism),
 }
 
@@ -65,6 +69,7 @@ impl Capability {
             Capability::CondStore => b"CONDSTORE",
             Capability::QResync => b"QRESYNC",
             Capability::LiteralPlus => b"LITERAL+",
+            Capability::LiteralMinus => b"LITERAL-",
             Capability::UnAuthenticate => b"UNAUTHENTICATE",
             Capability::StatusSize => b"STATUS=SIZE",
             Capability::ObjectId => b"OBJECTID",
@@ -92,16 +97,27 @@ impl Capability {
             Capability::CreateSpecialUse => b"CREATE-SPECIAL-USE",
             Capability::Move => b"MOVE",
             Capability::Utf8Accept => b"UTF8=ACCEPT",
+            Capability::CompressDeflate => b"COMPRESS=DEFLATE",
+            Capability::Quota => b"QUOTA",
+            Capability::QuotaResStorage => b"QUOTA=RES-STORAGE",
         });
     }
 
-    pub fn all_capabilities(is_authenticated: bool, is_tls: bool) -> Vec<Capability> {
+    pub fn all_capabilities(
+        is_authenticated: bool,
+        is_tls: bool,
+        literal_minus: bool,
+    ) -> Vec<Capability> {
         let mut capabilities = vec![
             Capability::IMAP4rev2,
             Capability::IMAP4rev1,
             Capability::Enable,
             Capability::SASLIR,
-            Capability::LiteralPlus,
+            if literal_minus {
+                Capability::LiteralMinus
+            } else {
+                Capability::LiteralPlus
+            },
             Capability::Id,
             Capability::Utf8Accept,
         ];
@@ -133,6 +149,9 @@ impl Capability {
                 Capability::StatusSize,
                 Capability::ObjectId,
                 Capability::Preview,
+                Capability::CompressDeflate,
+                Capability::Quota,
+                Capability::QuotaResStorage,
             ]);
         } else {
             capabilities.extend([
@@ -0,0 +1,74 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{quoted_string, ImapResponse};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arguments {
+    pub tag: String,
+    pub parameters: Vec<(String, Option<String>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub parameters: Vec<(String, String)>,
+}
+
+impl Arguments {
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(field))
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
+
+impl ImapResponse for Response {
+    fn serialize(self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(64);
+        if !self.parameters.is_empty() {
+            buf.extend_from_slice(b"* ID (");
+            for (pos, (name, value)) in self.parameters.iter().enumerate() {
+                if pos > 0 {
+                    buf.push(b' ');
+                }
+                quoted_string(&mut buf, name);
+                buf.push(b' ');
+                quoted_string(&mut buf, value);
+            }
+            buf.extend_from_slice(b")\r\n");
+        } else {
+            buf.extend_from_slice(b"* ID NIL\r\n");
+        }
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocol::ImapResponse;
+
+    #[test]
+    fn serialize_id() {
+        assert_eq!(
+            String::from_utf8(
+                super::Response {
+                    parameters: vec![
+                        ("name".to_string(), "Stalwart IMAP".to_string()),
+                        ("vendor".to_string(), "Example Inc.".to_string()),
+                    ],
+                }
+                .serialize()
+            )
+            .unwrap(),
+            "* ID (\"name\" \"Stalwart IMAP\" \"vendor\" \"Example Inc.\")\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(super::Response { parameters: vec![] }.serialize()).unwrap(),
+            "* ID NIL\r\n"
+        );
+    }
+}
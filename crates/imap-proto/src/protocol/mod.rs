@@ -16,15 +16,18 @@ pub mod acl;
 pub mod append;
 pub mod authenticate;
 pub mod capability;
+pub mod compress;
 pub mod copy_move;
 pub mod create;
 pub mod delete;
 pub mod enable;
 pub mod expunge;
 pub mod fetch;
+pub mod id;
 pub mod list;
 pub mod login;
 pub mod namespace;
+pub mod quota;
 pub mod rename;
 pub mod search;
 pub mod select;
@@ -356,6 +359,7 @@ impl ResponseCode {
             ResponseCode::ReadOnly => b"READ-ONLY",
             ResponseCode::ReadWrite => b"READ-WRITE",
             ResponseCode::ServerBug => b"SERVERBUG",
+            ResponseCode::TooBig => b"TOOBIG",
             ResponseCode::TryCreate => b"TRYCREATE",
             ResponseCode::UidNext => b"UIDNEXT",
             ResponseCode::UidNotSticky => b"UIDNOTSTICKY",
@@ -379,6 +383,7 @@ impl ResponseCode {
                 return;
             }
             ResponseCode::UseAttr => b"USEATTR",
+            ResponseCode::CompressionActive => b"COMPRESSIONACTIVE",
         });
     }
 
@@ -412,6 +417,7 @@ impl ResponseCode {
             ResponseCode::ReadOnly => "READ-ONLY",
             ResponseCode::ReadWrite => "READ-WRITE",
             ResponseCode::ServerBug => "SERVERBUG",
+            ResponseCode::TooBig => "TOOBIG",
             ResponseCode::TryCreate => "TRYCREATE",
             ResponseCode::UidNext => "UIDNEXT",
             ResponseCode::UidNotSticky => "UIDNOTSTICKY",
@@ -422,6 +428,7 @@ impl ResponseCode {
             ResponseCode::MailboxId { .. } => "MAILBOXID",
             ResponseCode::HighestModseq { .. } => "HIGHESTMODSEQ",
             ResponseCode::UseAttr => "USEATTR",
+            ResponseCode::CompressionActive => "COMPRESSIONACTIVE",
         }
     }
 }
@@ -608,6 +615,10 @@ impl Display for Command {
             Command::MyRights => write!(f, "MYRIGHTS"),
             Command::Unauthenticate => write!(f, "UNAUTHENTICATE"),
             Command::Id => write!(f, "ID"),
+            Command::Compress => write!(f, "COMPRESS"),
+            Command::GetQuota => write!(f, "GETQUOTA"),
+            Command::GetQuotaRoot => write!(f, "GETQUOTAROOT"),
+            Command::SetQuota => write!(f, "SETQUOTA"),
         }
     }
 }
@@ -0,0 +1,127 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utf7::utf7_encode;
+
+use super::quoted_string;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arguments {
+    pub tag: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quota {
+    pub root: String,
+    pub resources: Vec<QuotaResource>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaResource {
+    pub resource: Resource,
+    pub usage: u64,
+    pub limit: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Storage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaRoot {
+    pub mailbox_name: String,
+    pub quotas: Vec<Quota>,
+}
+
+impl Quota {
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(b"* QUOTA ");
+        quoted_string(buf, &self.root);
+        buf.extend_from_slice(b" (");
+        for (pos, resource) in self.resources.iter().enumerate() {
+            if pos > 0 {
+                buf.push(b' ');
+            }
+            buf.extend_from_slice(match resource.resource {
+                Resource::Storage => b"STORAGE ",
+            });
+            buf.extend_from_slice(resource.usage.to_string().as_bytes());
+            buf.push(b' ');
+            buf.extend_from_slice(resource.limit.to_string().as_bytes());
+        }
+        buf.extend_from_slice(b")\r\n");
+    }
+}
+
+impl QuotaRoot {
+    pub fn serialize(&self, buf: &mut Vec<u8>, is_rev2: bool) {
+        buf.extend_from_slice(b"* QUOTAROOT ");
+        if is_rev2 {
+            quoted_string(buf, &self.mailbox_name);
+        } else {
+            quoted_string(buf, &utf7_encode(&self.mailbox_name));
+        }
+        for quota in &self.quotas {
+            buf.push(b' ');
+            quoted_string(buf, &quota.root);
+        }
+        buf.extend_from_slice(b"\r\n");
+        for quota in &self.quotas {
+            quota.serialize(buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Quota, QuotaResource, QuotaRoot, Resource};
+
+    #[test]
+    fn serialize_quota() {
+        let quota = Quota {
+            root: "".to_string(),
+            resources: vec![QuotaResource {
+                resource: Resource::Storage,
+                usage: 10,
+                limit: 512,
+            }],
+        };
+
+        let mut buf = Vec::new();
+        quota.serialize(&mut buf);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "* QUOTA \"\" (STORAGE 10 512)\r\n"
+        );
+
+        let mut buf = Vec::new();
+        QuotaRoot {
+            mailbox_name: "INBOX".to_string(),
+            quotas: vec![quota],
+        }
+        .serialize(&mut buf, true);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            concat!(
+                "* QUOTAROOT \"INBOX\" \"\"\r\n",
+                "* QUOTA \"\" (STORAGE 10 512)\r\n"
+            )
+        );
+
+        let mut buf = Vec::new();
+        QuotaRoot {
+            mailbox_name: "Shared".to_string(),
+            quotas: vec![],
+        }
+        .serialize(&mut buf, true);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "* QUOTAROOT \"Shared\"\r\n"
+        );
+    }
+}
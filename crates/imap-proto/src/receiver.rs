@@ -8,10 +8,14 @@ use std::fmt::Display;
 
 use super::{ResponseCode, ResponseType};
 
+// Maximum size of a non-synchronizing literal under LITERAL- (RFC 7888)
+pub const MAX_NON_SYNC_LITERAL: u32 = 4096;
+
 #[derive(Debug, Clone)]
 pub enum Error {
     NeedsMoreData,
     NeedsLiteral { size: u32 },
+    NonSyncLiteral { size: u32 },
     Error { response: trc::Error },
 }
 
@@ -60,6 +64,7 @@ pub enum State {
     Literal { non_sync: bool },
     LiteralSeek { size: u32, non_sync: bool },
     LiteralData { remaining: u32 },
+    LiteralDiscard { remaining: u32, header: bool },
 }
 
 pub struct Receiver<T: CommandParser> {
@@ -69,6 +74,8 @@ pub struct Receiver<T: CommandParser> {
     pub max_request_size: usize,
     pub current_request_size: usize,
     pub start_state: State,
+    pub max_non_sync_literal: u32,
+    pub report_non_sync_literals: bool,
 }
 
 impl<T: CommandParser> Receiver<T> {
@@ -92,6 +99,30 @@ impl<T: CommandParser> Receiver<T> {
         }
     }
 
+    pub fn with_max_non_sync_literal(mut self, max_non_sync_literal: u32) -> Self {
+        self.max_non_sync_literal = max_non_sync_literal;
+        self
+    }
+
+    // Returns NonSyncLiteral before reading the data of a non-synchronizing
+    // literal, so that the caller can reject it before it is received
+    pub fn with_report_non_sync_literals(mut self) -> Self {
+        self.report_non_sync_literals = true;
+        self
+    }
+
+    // Skips the remaining data of a literal the client did not wait for,
+    // along with the rest of the command line
+    pub fn discard_literal(&mut self, size: u32) {
+        self.request = Request::default();
+        self.buf = Vec::with_capacity(10);
+        self.state = State::LiteralDiscard {
+            remaining: size,
+            header: false,
+        };
+        self.current_request_size = 0;
+    }
+
     pub fn error_reset(&mut self, message: impl Into<trc::Value>) -> Error {
         let request = std::mem::take(&mut self.request);
         let err = Error::err(
@@ -319,6 +350,22 @@ impl<T: CommandParser> Receiver<T> {
                                         "Literal exceeds the maximum request size of {} bytes.",
                                         self.max_request_size
                                     )));
+                                } else if non_sync && size > self.max_non_sync_literal {
+                                    // The client will send the data anyway, discard it
+                                    let tag = std::mem::take(&mut self.request.tag);
+                                    self.discard_literal(size);
+                                    self.state = State::LiteralDiscard {
+                                        remaining: size,
+                                        header: true,
+                                    };
+                                    return Err(Error::err_with_code(
+                                        if !tag.is_empty() { tag.into() } else { None },
+                                        format!(
+                                            "Non-synchronizing literals are limited to {} bytes.",
+                                            self.max_non_sync_literal
+                                        ),
+                                        ResponseCode::TooBig,
+                                    ));
                                 }
                                 self.state = State::LiteralSeek { size, non_sync };
                                 self.buf = Vec::with_capacity(size as usize);
@@ -360,6 +407,8 @@ impl<T: CommandParser> Receiver<T> {
                         }
                         if !non_sync {
                             return Err(Error::NeedsLiteral { size });
+                        } else if self.report_non_sync_literals && size > 0 {
+                            return Err(Error::NonSyncLiteral { size });
                         }
                     } else if !ch.is_ascii_whitespace() {
                         return Err(
@@ -378,6 +427,42 @@ impl<T: CommandParser> Receiver<T> {
                         self.state = State::Argument { last_ch: b' ' };
                     }
                 }
+                State::LiteralDiscard { remaining, header } => {
+                    if header || remaining == 0 {
+                        if ch == b'\n' {
+                            // The command continues after a non-synchronizing literal
+                            // at the end of the line, its data is also discarded
+                            let literal = std::mem::replace(&mut self.buf, Vec::with_capacity(10));
+                            self.state = if remaining > 0 {
+                                State::LiteralDiscard {
+                                    remaining,
+                                    header: false,
+                                }
+                            } else if let Some(size) = parse_non_sync_literal(&literal) {
+                                State::LiteralDiscard {
+                                    remaining: size,
+                                    header: size == 0,
+                                }
+                            } else {
+                                self.start_state
+                            };
+                        } else if ch == b'{' {
+                            self.buf.clear();
+                            self.buf.push(ch);
+                        } else if !self.buf.is_empty() {
+                            if self.buf.len() < 16 {
+                                self.buf.push(ch);
+                            } else {
+                                self.buf.clear();
+                            }
+                        }
+                    } else {
+                        self.state = State::LiteralDiscard {
+                            remaining: remaining - 1,
+                            header: false,
+                        };
+                    }
+                }
             }
         }
 
@@ -385,6 +470,15 @@ impl<T: CommandParser> Receiver<T> {
     }
 }
 
+// Returns the size of a "{n+}" literal marker found at the end of a line
+fn parse_non_sync_literal(line_end: &[u8]) -> Option<u32> {
+    let line_end = line_end.strip_suffix(b"\r").unwrap_or(line_end);
+    std::str::from_utf8(line_end.strip_prefix(b"{")?.strip_suffix(b"+}")?)
+        .ok()?
+        .parse()
+        .ok()
+}
+
 impl Token {
     pub fn unwrap_string(self) -> crate::parser::Result<String> {
         match self {
@@ -463,12 +557,20 @@ impl Display for Token {
 
 impl Error {
     pub fn err(tag: Option<String>, message: impl Into<trc::Value>) -> Self {
+        Self::err_with_code(tag, message, ResponseCode::Parse)
+    }
+
+    pub fn err_with_code(
+        tag: Option<String>,
+        message: impl Into<trc::Value>,
+        code: ResponseCode,
+    ) -> Self {
         Error::Error {
             response: trc::ImapEvent::Error
                 .ctx(trc::Key::Details, message)
                 .ctx_opt(trc::Key::Id, tag)
                 .ctx(trc::Key::Type, ResponseType::Bad)
-                .code(ResponseCode::Parse),
+                .code(code),
         }
     }
 }
@@ -482,6 +584,8 @@ impl<T: CommandParser> Default for Receiver<T> {
             start_state: State::Start,
             max_request_size: 25 * 1024 * 1024,
             current_request_size: 0,
+            max_non_sync_literal: u32::MAX,
+            report_non_sync_literals: false,
         }
     }
 }
@@ -543,7 +647,7 @@ mod tests {
 
     use crate::Command;
 
-    use super::{Error, Receiver, Request, Token};
+    use super::{Error, Receiver, Request, Token, MAX_NON_SYNC_LITERAL};
 
     #[test]
     fn receiver_parse_ok() {
@@ -1016,4 +1120,85 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn receiver_parse_non_sync_literal() {
+        let mut receiver = Receiver::<Command>::new()
+            .with_max_non_sync_literal(MAX_NON_SYNC_LITERAL)
+            .with_report_non_sync_literals();
+
+        // Within the LITERAL- limit
+        let mut bytes = b"A1 APPEND INBOX {5+}\r\nhello\r\n".iter();
+        match receiver.parse(&mut bytes) {
+            Err(Error::NonSyncLiteral { size: 5 }) => {}
+            result => panic!("Expected non-sync literal, got: {:?}", result),
+        }
+        assert_eq!(
+            receiver.parse(&mut bytes).unwrap(),
+            Request {
+                tag: "A1".to_string(),
+                command: Command::Append,
+                tokens: vec![
+                    Token::Argument(b"INBOX".to_vec()),
+                    Token::Argument(b"hello".to_vec()),
+                ],
+            }
+        );
+
+        // Above the LITERAL- limit, the literal data is discarded
+        let frame = format!(
+            "A2 APPEND INBOX {{5000+}}\r\n{}\r\nA3 NOOP\r\n",
+            "A04 NOOP\r\n".repeat(500)
+        );
+        let mut bytes = frame.as_bytes().iter();
+        match receiver.parse(&mut bytes) {
+            Err(Error::Error { response }) => {
+                assert_eq!(response.value_as_str(trc::Key::Id), Some("A2"));
+                assert_eq!(response.value_as_str(trc::Key::Code), Some("TOOBIG"));
+            }
+            result => panic!("Expected error, got: {:?}", result),
+        }
+        assert_eq!(receiver.parse(&mut bytes).unwrap().tag, "A3");
+
+        // Literal discarded by the caller after NonSyncLiteral
+        let mut bytes = b"A5 APPEND INBOX (\\Seen) {5+}\r\nA6 NOOP\r\nA7 NOOP\r\n".iter();
+        match receiver.parse(&mut bytes) {
+            Err(Error::NonSyncLiteral { size: 5 }) => {}
+            result => panic!("Expected non-sync literal, got: {:?}", result),
+        }
+        receiver.discard_literal(5);
+        assert_eq!(receiver.parse(&mut bytes).unwrap().tag, "A7");
+
+        // Non-synchronizing literals that follow a discarded literal are also discarded
+        let frame = format!(
+            "A9 APPEND INBOX {{5000+}}\r\n{} {{10+}}\r\nA10 NOOP\r\n\r\nA11 NOOP\r\n",
+            "a".repeat(5000)
+        );
+        let mut bytes = frame.as_bytes().iter();
+        match receiver.parse(&mut bytes) {
+            Err(Error::Error { response }) => {
+                assert_eq!(response.value_as_str(trc::Key::Id), Some("A9"));
+            }
+            result => panic!("Expected error, got: {:?}", result),
+        }
+        assert_eq!(receiver.parse(&mut bytes).unwrap().tag, "A11");
+
+        let mut bytes =
+            b"A12 APPEND INBOX {5+}\r\nA13 N {10+}\r\nA14 NOOP\r\n {0+}\r\n\r\nA15 NOOP\r\n".iter();
+        match receiver.parse(&mut bytes) {
+            Err(Error::NonSyncLiteral { size: 5 }) => {}
+            result => panic!("Expected non-sync literal, got: {:?}", result),
+        }
+        receiver.discard_literal(5);
+        assert_eq!(receiver.parse(&mut bytes).unwrap().tag, "A15");
+
+        // Synchronizing literals are not limited
+        let frame = format!("A8 APPEND INBOX {{5000}}\r\n{}\r\n", "a".repeat(5000));
+        let mut bytes = frame.as_bytes().iter();
+        match receiver.parse(&mut bytes) {
+            Err(Error::NeedsLiteral { size: 5000 }) => {}
+            result => panic!("Expected literal, got: {:?}", result),
+        }
+        assert_eq!(receiver.parse(&mut bytes).unwrap().tokens.len(), 2);
+    }
 }
@@ -78,7 +78,7 @@ pub fn utf7_encode(text: &str) -> String {
     let mut u: u32 = 0;
 
     for ch_ in text.chars() {
-        let ch = ch_ as u16;
+        let ch = ch_ as u32;
 
         if (0x20..0x7f).contains(&ch) {
             if shifted {
@@ -101,12 +101,15 @@ pub fn utf7_encode(text: &str) -> String {
                 shifted = true;
             }
 
-            u = (u << 16) | ch as u32;
-            bits += 16;
+            // Characters outside the BMP are written as surrogate pairs
+            for ch in ch_.encode_utf16(&mut [0u16; 2]) {
+                u = (u << 16) | *ch as u32;
+                bits += 16;
 
-            while bits >= 6 {
-                result.push(char::from(UTF_7_MAP[((u >> (bits - 6)) & 0x3f) as usize]));
-                bits -= 6;
+                while bits >= 6 {
+                    result.push(char::from(UTF_7_MAP[((u >> (bits - 6)) & 0x3f) as usize]));
+                    bits -= 6;
+                }
             }
         }
     }
@@ -130,8 +133,19 @@ pub fn utf7_maybe_decode(text: String, version: ProtocolVersion) -> String {
     }
 }
 
+#[inline(always)]
+pub fn utf7_strict_decode(text: String, version: ProtocolVersion) -> Option<String> {
+    if version.is_rev2() {
+        Some(text)
+    } else {
+        // Only accept names that encode back to the exact same modified UTF-7
+        utf7_decode(text.as_bytes()).filter(|decoded| utf7_encode(decoded) == text)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::protocol::ProtocolVersion;
 
     #[test]
     fn utf7_decode() {
@@ -178,6 +192,7 @@ mod tests {
             ("&ZeVnLIqe-", "日本語"),
             ("Item 3 is &AKM-1.", "Item 3 is £1."),
             ("Plus minus &- -&- &--", "Plus minus & -& &-"),
+            ("Party &2DzfiQ-", "Party 🎉"),
         ] {
             assert_eq!(
                 super::utf7_encode(input),
@@ -187,4 +202,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn utf7_strict_decode() {
+        for (input, expected_result) in [
+            ("&ZeVnLIqe-", Some("日本語")),
+            ("Plus minus &- -&- &--", Some("Plus minus & -& &-")),
+            ("Party &2DzfiQ-", Some("Party 🎉")),
+            ("Café", None),
+            ("Unterminated &ZeVnLIqe", None),
+            ("Lone &", None),
+            ("Encoded ASCII &AGE-", None),
+            ("Invalid &!!!-", None),
+        ] {
+            assert_eq!(
+                super::utf7_strict_decode(input.to_string(), ProtocolVersion::Rev1).as_deref(),
+                expected_result,
+                "while decoding {:?}",
+                input
+            );
+        }
+
+        assert_eq!(
+            super::utf7_strict_decode("Café".to_string(), ProtocolVersion::Rev2).as_deref(),
+            Some("Café")
+        );
+    }
 }
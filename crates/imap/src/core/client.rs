@@ -9,7 +9,7 @@ use std::{iter::Peekable, sync::Arc, vec::IntoIter};
 use common::listener::{limiter::ConcurrencyLimiter, SessionResult, SessionStream};
 use imap_proto::{
     receiver::{self, Request},
-    Command, ResponseType, StatusResponse,
+    Command, ResponseCode, ResponseType, StatusResponse,
 };
 use jmap::auth::rate_limit::ConcurrencyLimiters;
 
@@ -27,12 +27,20 @@ impl<T: SessionStream> Session<T> {
         let mut bytes = bytes.iter();
         let mut requests = Vec::with_capacity(2);
         let mut needs_literal = None;
+        let mut compress_buf = None;
 
         loop {
             match self.receiver.parse(&mut bytes) {
                 Ok(request) => match self.is_allowed(request).await {
                     Ok(request) => {
+                        let is_compress = request.command == Command::Compress;
                         requests.push(request);
+
+                        // Anything following COMPRESS is already deflated
+                        if is_compress {
+                            compress_buf = bytes.as_slice().to_vec().into();
+                            break;
+                        }
                     }
                     Err(err) => {
                         if !self.write_error(err).await {
@@ -44,14 +52,36 @@ impl<T: SessionStream> Session<T> {
                     break;
                 }
                 Err(receiver::Error::NeedsLiteral { size }) => {
+                    // Reject messages that do not fit in the quota before they are sent
+                    if let Some(err) = self.append_quota_error(size).await {
+                        self.receiver.error_reset("Disk quota exceeded.");
+                        if !self.write_error(err).await {
+                            return SessionResult::Close;
+                        }
+                        break;
+                    }
                     needs_literal = size.into();
                     break;
                 }
+                Err(receiver::Error::NonSyncLiteral { size }) => {
+                    // The client does not wait for a continuation, the data that
+                    // follows has to be skipped when the message is rejected
+                    if let Some(err) = self.append_quota_error(size).await {
+                        self.receiver.discard_literal(size);
+                        if !self.write_error(err).await {
+                            return SessionResult::Close;
+                        }
+                    }
+                }
                 Err(receiver::Error::Error { response }) => {
                     if !self.write_error(response).await {
                         return SessionResult::Close;
                     }
-                    break;
+
+                    // Keep reading the data of a rejected non-synchronizing literal
+                    if !matches!(self.receiver.state, receiver::State::LiteralDiscard { .. }) {
+                        break;
+                    }
                 }
             }
         }
@@ -207,6 +237,22 @@ impl<T: SessionStream> Session<T> {
                     .handle_id(request)
                     .await
                     .map(|_| SessionResult::Continue),
+                Command::GetQuota => self
+                    .handle_get_quota(request)
+                    .await
+                    .map(|_| SessionResult::Continue),
+                Command::GetQuotaRoot => self
+                    .handle_get_quota_root(request)
+                    .await
+                    .map(|_| SessionResult::Continue),
+                Command::SetQuota => self
+                    .handle_set_quota(request)
+                    .await
+                    .map(|_| SessionResult::Continue),
+                Command::Compress => self
+                    .handle_compress(request, compress_buf.take().unwrap_or_default())
+                    .await
+                    .map(|_| SessionResult::Continue),
             };
 
             match result {
@@ -274,7 +320,12 @@ impl<T: SessionStream> Session<T> {
             Command::Capability | Command::Noop | Command::Logout | Command::Id => Ok(request),
             Command::StartTls => {
                 if !self.is_tls {
-                    if self.instance.acceptor.is_tls() {
+                    if self.compress.is_active() {
+                        Err(trc::ImapEvent::Error
+                            .into_err()
+                            .details("TLS cannot be negotiated after COMPRESS.")
+                            .id(request.tag))
+                    } else if self.instance.acceptor.is_tls() {
                         Ok(request)
                     } else {
                         Err(trc::ImapEvent::Error
@@ -289,6 +340,22 @@ impl<T: SessionStream> Session<T> {
                         .id(request.tag))
                 }
             }
+            Command::Compress => {
+                if self.compress.is_active() {
+                    Err(trc::ImapEvent::Error
+                        .into_err()
+                        .details("Compression is already active.")
+                        .code(ResponseCode::CompressionActive)
+                        .id(request.tag))
+                } else if let State::NotAuthenticated { .. } = state {
+                    Err(trc::ImapEvent::Error
+                        .into_err()
+                        .details("Not authenticated.")
+                        .id(request.tag))
+                } else {
+                    Ok(request)
+                }
+            }
             Command::Authenticate => {
                 if let State::NotAuthenticated { .. } = state {
                     Ok(request)
@@ -335,7 +402,10 @@ impl<T: SessionStream> Session<T> {
             | Command::GetAcl
             | Command::ListRights
             | Command::MyRights
-            | Command::Unauthenticate => {
+            | Command::Unauthenticate
+            | Command::GetQuota
+            | Command::GetQuotaRoot
+            | Command::SetQuota => {
                 if let State::Authenticated { .. } | State::Selected { .. } = state {
                     Ok(request)
                 } else {
@@ -0,0 +1,171 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{
+    borrow::Cow,
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use common::listener::SessionStream;
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+const READ_BUF_SIZE: usize = 8192;
+
+// Raw DEFLATE stream (no zlib headers) as required by RFC 4978
+pub struct DeflateStream<T> {
+    inner: T,
+    deflate: Compress,
+    inflate: Decompress,
+    rx_buf: Vec<u8>,
+    rx_pos: usize,
+    tx_buf: Vec<u8>,
+    tx_pos: usize,
+    needs_flush: bool,
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> DeflateStream<T> {
+    // Bytes received after the COMPRESS command but before the stream was
+    // upgraded are already deflated and are passed in as `rx_buf`.
+    pub fn new(inner: T, rx_buf: Vec<u8>) -> Self {
+        DeflateStream {
+            inner,
+            deflate: Compress::new(Compression::default(), false),
+            inflate: Decompress::new(false),
+            rx_buf,
+            rx_pos: 0,
+            tx_buf: Vec::with_capacity(READ_BUF_SIZE),
+            tx_pos: 0,
+            needs_flush: false,
+        }
+    }
+
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.tx_pos < self.tx_buf.len() {
+            match ready!(Pin::new(&mut self.inner).poll_write(cx, &self.tx_buf[self.tx_pos..])) {
+                Ok(0) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                Ok(bytes_written) => {
+                    self.tx_pos += bytes_written;
+                }
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+        self.tx_buf.clear();
+        self.tx_pos = 0;
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn compress(&mut self, mut bytes: &[u8], flush: FlushCompress) -> io::Result<()> {
+        loop {
+            self.tx_buf.reserve(bytes.len() + 64);
+            let spare = self.tx_buf.capacity() - self.tx_buf.len();
+            let total_in = self.deflate.total_in();
+            let total_out = self.deflate.total_out();
+            self.deflate
+                .compress_vec(bytes, &mut self.tx_buf, flush)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+            bytes = &bytes[(self.deflate.total_in() - total_in) as usize..];
+
+            // Keep going until all input was consumed and the output buffer was not exhausted
+            if bytes.is_empty() && ((self.deflate.total_out() - total_out) as usize) < spare {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> AsyncRead for DeflateStream<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            // Inflate any pending input
+            if this.rx_pos < this.rx_buf.len() {
+                let total_in = this.inflate.total_in();
+                let total_out = this.inflate.total_out();
+                this.inflate
+                    .decompress(
+                        &this.rx_buf[this.rx_pos..],
+                        buf.initialize_unfilled(),
+                        FlushDecompress::None,
+                    )
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                let consumed = (this.inflate.total_in() - total_in) as usize;
+                let produced = (this.inflate.total_out() - total_out) as usize;
+                this.rx_pos += consumed;
+
+                if produced > 0 {
+                    buf.advance(produced);
+                    return Poll::Ready(Ok(()));
+                } else if consumed > 0 {
+                    continue;
+                }
+            }
+
+            // Read more compressed data from the underlying stream
+            this.rx_buf.drain(..this.rx_pos);
+            this.rx_pos = 0;
+            let mut bytes = [0u8; READ_BUF_SIZE];
+            let mut read_buf = ReadBuf::new(&mut bytes);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read_buf))?;
+            if read_buf.filled().is_empty() {
+                // EOF
+                return Poll::Ready(Ok(()));
+            }
+            this.rx_buf.extend_from_slice(read_buf.filled());
+        }
+    }
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> AsyncWrite for DeflateStream<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        this.compress(buf, FlushCompress::None)?;
+        this.needs_flush = true;
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.needs_flush {
+            this.compress(&[], FlushCompress::Sync)?;
+            this.needs_flush = false;
+        }
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+impl<T: SessionStream> SessionStream for DeflateStream<T> {
+    fn is_tls(&self) -> bool {
+        self.inner.is_tls()
+    }
+
+    fn tls_version_and_cipher(&self) -> (Cow<'static, str>, Cow<'static, str>) {
+        self.inner.tls_version_and_cipher()
+    }
+}
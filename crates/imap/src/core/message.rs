@@ -9,12 +9,15 @@ use std::{collections::BTreeMap, sync::Arc};
 use ahash::AHashMap;
 use common::listener::SessionStream;
 use imap_proto::protocol::{expunge, select::Exists, Sequence};
-use jmap::mailbox::UidMailbox;
+use jmap::mailbox::{set::SCHEMA, UidMailbox};
 use jmap_proto::{
-    object::Object,
+    object::{index::ObjectIndexBuilder, Object},
     types::{collection::Collection, property::Property, value::Value},
 };
-use store::write::assert::HashedValue;
+use store::{
+    write::{assert::HashedValue, BatchBuilder, ValueClass},
+    ValueKey,
+};
 use trc::AddContext;
 use utils::lru_cache::LruCached;
 
@@ -240,6 +243,115 @@ impl<T: SessionStream> SessionData<T> {
             })
             .map(|v| v as u32)
     }
+
+    // A UID counter behind the highest UID in use (e.g. after a partial restore)
+    // would assign known UIDs again, so clients are told to resynchronize. Returns
+    // the new UID validity when the counter had to be moved forward.
+    pub async fn check_uid_counter(
+        &self,
+        mailbox: &MailboxId,
+        uid_max: u32,
+    ) -> trc::Result<Option<u32>> {
+        if uid_max > 0 && self.get_last_uid(mailbox).await? < uid_max as i64 {
+            self.reset_uid_validity(mailbox, uid_max).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn get_last_uid(&self, mailbox: &MailboxId) -> trc::Result<i64> {
+        self.jmap
+            .core
+            .storage
+            .data
+            .get_counter(ValueKey {
+                account_id: mailbox.account_id,
+                collection: Collection::Mailbox.into(),
+                document_id: mailbox.mailbox_id,
+                class: ValueClass::Property(Property::EmailIds.into()),
+            })
+            .await
+            .add_context(|e| e.caused_by(trc::location!()).account_id(mailbox.account_id))
+    }
+
+    async fn reset_uid_validity(&self, mailbox: &MailboxId, uid_max: u32) -> trc::Result<u32> {
+        let mut try_count = 0;
+
+        loop {
+            let current = self
+                .jmap
+                .get_property::<HashedValue<Object<Value>>>(
+                    mailbox.account_id,
+                    Collection::Mailbox,
+                    mailbox.mailbox_id,
+                    &Property::Value,
+                )
+                .await?
+                .ok_or_else(|| {
+                    trc::ImapEvent::Error
+                        .caused_by(trc::location!())
+                        .details("Mailbox unavailable")
+                        .account_id(mailbox.account_id)
+                        .collection(Collection::Mailbox)
+                        .document_id(mailbox.mailbox_id)
+                })?;
+            let uid_validity = current
+                .inner
+                .get(&Property::Cid)
+                .as_uint()
+                .unwrap_or_default() as u32;
+
+            // Another session may have reset the UID validity in the meantime
+            let last_uid = self.get_last_uid(mailbox).await?;
+            if last_uid >= uid_max as i64 {
+                return Ok(uid_validity);
+            }
+
+            // UID validity values must change, zero is not a valid UID validity
+            let uid_validity = match uid_validity.wrapping_add(1) {
+                0 => 1,
+                uid_validity => uid_validity,
+            };
+
+            // Move the UID counter past the highest UID in use
+            let mut batch = BatchBuilder::new();
+            batch
+                .with_account_id(mailbox.account_id)
+                .with_collection(Collection::Mailbox)
+                .update_document(mailbox.mailbox_id)
+                .add(Property::EmailIds, uid_max as i64 - last_uid)
+                .custom(
+                    ObjectIndexBuilder::new(SCHEMA)
+                        .with_current(current)
+                        .with_changes(
+                            Object::with_capacity(1).with_property(
+                                Property::Cid,
+                                Value::UnsignedInt(uid_validity as u64),
+                            ),
+                        ),
+                );
+            match self.jmap.write_batch(batch).await {
+                Ok(_) => {
+                    trc::event!(
+                        Imap(trc::ImapEvent::UidValidityReset),
+                        AccountId = mailbox.account_id,
+                        MailboxId = mailbox.mailbox_id,
+                        Uid = uid_max,
+                        Value = uid_validity,
+                        SpanId = self.session_id
+                    );
+
+                    return Ok(uid_validity);
+                }
+                Err(err) if err.is_assertion_failure() && try_count < MAX_RETRIES => {
+                    try_count += 1;
+                }
+                Err(err) => {
+                    return Err(err.caused_by(trc::location!()));
+                }
+            }
+        }
+    }
 }
 
 impl SelectedMailbox {
@@ -12,7 +12,7 @@ use std::{
 
 use ahash::AHashMap;
 use common::listener::{limiter::InFlight, ServerInstance, SessionStream};
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use imap_proto::{
     protocol::{list::Attribute, ProtocolVersion},
     receiver::Receiver,
@@ -30,6 +30,7 @@ use trc::AddContext;
 use utils::lru_cache::LruCache;
 
 pub mod client;
+pub mod compress;
 pub mod mailbox;
 pub mod message;
 pub mod session;
@@ -59,6 +60,9 @@ pub struct Inner {
 
     pub cache_account: LruCache<AccountId, Arc<Account>>,
     pub cache_mailbox: LruCache<MailboxId, Arc<MailboxState>>,
+
+    // Accounts already reported with negative quota usage
+    pub negative_quota: DashSet<u32>,
 }
 
 pub struct IMAP {}
@@ -73,6 +77,7 @@ pub struct Session<T: SessionStream> {
     pub is_tls: bool,
     pub is_condstore: bool,
     pub is_qresync: bool,
+    pub compress: CompressState,
     pub stream_rx: ReadHalf<T>,
     pub stream_tx: Arc<tokio::sync::Mutex<WriteHalf<T>>>,
     pub in_flight: InFlight,
@@ -80,6 +85,24 @@ pub struct Session<T: SessionStream> {
     pub session_id: u64,
 }
 
+#[derive(Debug, Default)]
+pub enum CompressState {
+    #[default]
+    None,
+    Pending(Vec<u8>),
+    Active,
+}
+
+impl CompressState {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, CompressState::Pending(_))
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self, CompressState::Active)
+    }
+}
+
 pub struct SessionData<T: SessionStream> {
     pub account_id: u32,
     pub jmap: JMAP,
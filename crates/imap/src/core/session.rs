@@ -9,13 +9,13 @@ use std::sync::Arc;
 use common::listener::{stream::NullIo, SessionData, SessionManager, SessionResult, SessionStream};
 use imap_proto::{
     protocol::{ProtocolVersion, SerializeResponse},
-    receiver::Receiver,
+    receiver::{Receiver, MAX_NON_SYNC_LITERAL},
 };
 use jmap::JMAP;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio_rustls::server::TlsStream;
 
-use super::{ImapSessionManager, Session, State};
+use super::{compress::DeflateStream, CompressState, ImapSessionManager, Session, State};
 
 impl SessionManager for ImapSessionManager {
     #[allow(clippy::manual_async_fn)]
@@ -25,9 +25,19 @@ impl SessionManager for ImapSessionManager {
     ) -> impl std::future::Future<Output = ()> + Send {
         async move {
             if let Ok(mut session) = Session::new(session, self).await {
-                if session.handle_conn().await && session.instance.acceptor.is_tls() {
-                    if let Ok(mut session) = session.into_tls().await {
-                        session.handle_conn().await;
+                if session.handle_conn().await {
+                    if session.compress.is_pending() {
+                        if let Ok(mut session) = session.into_compressed() {
+                            session.handle_conn().await;
+                        }
+                    } else if session.instance.acceptor.is_tls() {
+                        if let Ok(mut session) = session.into_tls().await {
+                            if session.handle_conn().await && session.compress.is_pending() {
+                                if let Ok(mut session) = session.into_compressed() {
+                                    session.handle_conn().await;
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -58,7 +68,11 @@ impl<T: SessionStream> Session<T> {
                         Ok(Ok(bytes_read)) => {
                             if bytes_read > 0 {
                                 match self.ingest(&buf[..bytes_read]).await {
-                                    SessionResult::Continue => (),
+                                    SessionResult::Continue => {
+                                        if self.compress.is_pending() {
+                                            return true;
+                                        }
+                                    }
                                     SessionResult::UpgradeTls => {
                                         return true;
                                     }
@@ -137,12 +151,19 @@ impl<T: SessionStream> Session<T> {
         let jmap = JMAP::from(manager.imap.jmap_instance);
 
         Ok(Session {
-            receiver: Receiver::with_max_request_size(jmap.core.imap.max_request_size),
+            receiver: Receiver::with_max_request_size(jmap.core.imap.max_request_size)
+                .with_max_non_sync_literal(if jmap.core.imap.literal_minus {
+                    MAX_NON_SYNC_LITERAL
+                } else {
+                    u32::MAX
+                })
+                .with_report_non_sync_literals(),
             version: ProtocolVersion::Rev1,
             state: State::NotAuthenticated { auth_failures: 0 },
             is_tls,
             is_condstore: false,
             is_qresync: false,
+            compress: CompressState::None,
             jmap,
             imap: manager.imap.imap_inner,
             instance: session.instance,
@@ -201,6 +222,67 @@ impl<T: SessionStream> Session<T> {
             is_tls: true,
             is_condstore: self.is_condstore,
             is_qresync: self.is_qresync,
+            compress: self.compress,
+            session_id: self.session_id,
+            in_flight: self.in_flight,
+            remote_addr: self.remote_addr,
+            stream_rx,
+            stream_tx,
+        })
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn into_compressed(self) -> Result<Session<DeflateStream<T>>, ()> {
+        // Drop references to write half from state
+        let state = if let Some(state) =
+            self.state
+                .try_replace_stream_tx(Arc::new(tokio::sync::Mutex::new(
+                    tokio::io::split(NullIo::default()).1,
+                ))) {
+            state
+        } else {
+            trc::event!(
+                Network(trc::NetworkEvent::SplitError),
+                SpanId = self.session_id,
+                Details = "Failed to obtain write half state"
+            );
+            return Err(());
+        };
+
+        // Take ownership of WriteHalf and unsplit it from ReadHalf
+        let stream = if let Ok(stream_tx) =
+            Arc::try_unwrap(self.stream_tx).map(|mutex| mutex.into_inner())
+        {
+            self.stream_rx.unsplit(stream_tx)
+        } else {
+            trc::event!(
+                Network(trc::NetworkEvent::SplitError),
+                SpanId = self.session_id,
+                Details = "Failed to take ownership of write half"
+            );
+
+            return Err(());
+        };
+
+        // Any data received after the COMPRESS command is already deflated
+        let rx_buf = match self.compress {
+            CompressState::Pending(rx_buf) => rx_buf,
+            _ => Vec::new(),
+        };
+        let (stream_rx, stream_tx) = tokio::io::split(DeflateStream::new(stream, rx_buf));
+        let stream_tx = Arc::new(tokio::sync::Mutex::new(stream_tx));
+
+        Ok(Session {
+            jmap: self.jmap,
+            imap: self.imap,
+            instance: self.instance,
+            receiver: self.receiver,
+            version: self.version,
+            state: state.try_replace_stream_tx(stream_tx.clone()).unwrap(),
+            is_tls: self.is_tls,
+            is_condstore: self.is_condstore,
+            is_qresync: self.is_qresync,
+            compress: CompressState::Active,
             session_id: self.session_id,
             in_flight: self.in_flight,
             remote_addr: self.remote_addr,
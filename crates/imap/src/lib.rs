@@ -7,7 +7,7 @@
 use core::{ImapInstance, Inner, IMAP};
 use std::{collections::hash_map::RandomState, sync::Arc};
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use imap_proto::{protocol::capability::Capability, ResponseCode, StatusResponse};
 use jmap::JmapInstance;
 use utils::{
@@ -27,16 +27,17 @@ impl IMAP {
             .unwrap_or(32)
             .next_power_of_two() as usize;
         let capacity = config.property("cache.capacity").unwrap_or(100);
+        let literal_minus = jmap_instance.core.load().imap.literal_minus;
 
         let inner = Inner {
             greeting_plain: StatusResponse::ok(SERVER_GREETING)
                 .with_code(ResponseCode::Capability {
-                    capabilities: Capability::all_capabilities(false, false),
+                    capabilities: Capability::all_capabilities(false, false, literal_minus),
                 })
                 .into_bytes(),
             greeting_tls: StatusResponse::ok(SERVER_GREETING)
                 .with_code(ResponseCode::Capability {
-                    capabilities: Capability::all_capabilities(false, true),
+                    capabilities: Capability::all_capabilities(false, true, literal_minus),
                 })
                 .into_bytes(),
             rate_limiter: DashMap::with_capacity_and_hasher_and_shard_amount(
@@ -50,6 +51,7 @@ impl IMAP {
             cache_mailbox: LruCache::with_capacity(
                 config.property("cache.mailbox.size").unwrap_or(2048),
             ),
+            negative_quota: DashSet::new(),
         };
 
         ImapInstance {
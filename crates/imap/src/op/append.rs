@@ -9,11 +9,12 @@ use std::{sync::Arc, time::Instant};
 use imap_proto::{
     protocol::{append::Arguments, select::HighestModSeq},
     receiver::Request,
+    utf7::utf7_maybe_decode,
     Command, ResponseCode, StatusResponse,
 };
 
 use crate::{
-    core::{ImapUidToId, MailboxId, SelectedMailbox, Session, SessionData},
+    core::{ImapUidToId, MailboxId, SelectedMailbox, Session, SessionData, State},
     spawn_op,
 };
 use common::listener::SessionStream;
@@ -55,6 +56,48 @@ impl<T: SessionStream> Session<T> {
             data.write_bytes(response).await
         })
     }
+
+    pub async fn check_append_quota(&self, literal_size: u32) -> trc::Result<()> {
+        let data = match &self.state {
+            State::Authenticated { data } | State::Selected { data, .. } => data,
+            State::NotAuthenticated { .. } => return Ok(()),
+        };
+
+        // The mailbox name is the first argument of an APPEND
+        if let Some(mailbox) = self
+            .receiver
+            .request
+            .tokens
+            .first()
+            .and_then(|token| token.clone().unwrap_string().ok())
+            .and_then(|name| data.get_mailbox_by_name(&utf7_maybe_decode(name, self.version)))
+        {
+            let account_quota = data.get_access_token().await?.quota as i64;
+            data.jmap
+                .has_available_quota(mailbox.account_id, account_quota, literal_size as i64)
+                .await
+        } else {
+            Ok(())
+        }
+    }
+
+    // Returns the OVERQUOTA response for an APPEND literal that does not fit in the quota
+    pub async fn append_quota_error(&self, literal_size: u32) -> Option<trc::Error> {
+        if self.receiver.request.command == Command::Append {
+            match self.check_append_quota(literal_size).await {
+                Err(err) if err.matches(trc::EventType::Limit(trc::LimitEvent::Quota)) => {
+                    return err
+                        .details("Disk quota exceeded.")
+                        .code(ResponseCode::OverQuota)
+                        .id(self.receiver.request.tag.clone())
+                        .into();
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
 }
 
 impl<T: SessionStream> SessionData<T> {
@@ -136,7 +136,11 @@ impl<T: SessionStream> Session<T> {
         self.write_bytes(
             StatusResponse::ok("Authentication successful")
                 .with_code(ResponseCode::Capability {
-                    capabilities: Capability::all_capabilities(true, self.is_tls),
+                    capabilities: Capability::all_capabilities(
+                        true,
+                        self.is_tls,
+                        self.jmap.core.imap.literal_minus,
+                    ),
                 })
                 .with_tag(tag)
                 .into_bytes(),
@@ -11,7 +11,7 @@ use common::listener::SessionStream;
 use imap_proto::{
     protocol::{
         capability::{Capability, Response},
-        ImapResponse,
+        id, ImapResponse,
     },
     receiver::Request,
     Command, StatusResponse,
@@ -36,6 +36,7 @@ impl<T: SessionStream> Session<T> {
                         capabilities: Capability::all_capabilities(
                             self.state.is_authenticated(),
                             self.is_tls,
+                            self.jmap.core.imap.literal_minus,
                         ),
                     }
                     .serialize(),
@@ -46,25 +47,31 @@ impl<T: SessionStream> Session<T> {
 
     pub async fn handle_id(&mut self, request: Request<Command>) -> trc::Result<()> {
         let op_start = Instant::now();
+        let arguments = request.parse_id()?;
+
         trc::event!(
             Imap(trc::ImapEvent::Id),
             SpanId = self.session_id,
+            Details = arguments.get("name").map(|name| name.to_string()),
+            Version = arguments.get("version").map(|version| version.to_string()),
             Elapsed = op_start.elapsed()
         );
 
+        let mut parameters = vec![
+            ("name".to_string(), "Stalwart IMAP".to_string()),
+            ("version".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        ];
+        if let Some(vendor) = &self.jmap.core.imap.id_vendor {
+            parameters.push(("vendor".to_string(), vendor.clone()));
+        }
+        if let Some(support_url) = &self.jmap.core.imap.id_support_url {
+            parameters.push(("support-url".to_string(), support_url.clone()));
+        }
+
         self.write_bytes(
             StatusResponse::completed(Command::Id)
-                .with_tag(request.tag)
-                .serialize(
-                    concat!(
-                        "* ID (\"name\" \"Stalwart IMAP\" \"version\" \"",
-                        env!("CARGO_PKG_VERSION"),
-                        "\" \"vendor\" \"Stalwart Labs Ltd.\" ",
-                        "\"support-url\" \"https://stalw.art\")\r\n"
-                    )
-                    .as_bytes()
-                    .to_vec(),
-                ),
+                .with_tag(arguments.tag)
+                .serialize(id::Response { parameters }.serialize()),
         )
         .await
     }
@@ -0,0 +1,39 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::time::Instant;
+
+use crate::core::{CompressState, Session};
+use common::listener::SessionStream;
+use imap_proto::{receiver::Request, Command, StatusResponse};
+
+impl<T: SessionStream> Session<T> {
+    pub async fn handle_compress(
+        &mut self,
+        request: Request<Command>,
+        rx_buf: Vec<u8>,
+    ) -> trc::Result<()> {
+        let op_start = Instant::now();
+        let arguments = request.parse_compress()?;
+
+        // The tagged response is the last data sent uncompressed
+        self.write_bytes(
+            StatusResponse::ok("DEFLATE active")
+                .with_tag(arguments.tag)
+                .into_bytes(),
+        )
+        .await?;
+        self.compress = CompressState::Pending(rx_buf);
+
+        trc::event!(
+            Imap(trc::ImapEvent::Compress),
+            SpanId = self.session_id,
+            Elapsed = op_start.elapsed()
+        );
+
+        Ok(())
+    }
+}
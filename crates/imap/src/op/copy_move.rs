@@ -160,72 +160,21 @@ impl<T: SessionStream> SessionData<T> {
         if src_mailbox.id.account_id == dest_mailbox.account_id {
             // Mailboxes are in the same account
             let account_id = src_mailbox.id.account_id;
-            let dest_mailbox_id = UidMailbox::new_unassigned(dest_mailbox_id);
             for (id, imap_id) in ids {
-                // Obtain mailbox tags
-                let (mut mailboxes, thread_id) = if let Some(result) = self
-                    .get_mailbox_tags(account_id, id)
+                if let Some(assigned_uids) = self
+                    .copy_move_message(
+                        account_id,
+                        id,
+                        src_mailbox.id.mailbox_id,
+                        dest_mailbox_id,
+                        is_move,
+                        &mut changelog,
+                    )
                     .await
                     .imap_ctx(&arguments.tag, trc::location!())?
                 {
-                    result
-                } else {
-                    continue;
-                };
-
-                // Make sure the message still belongs to this mailbox
-                if !mailboxes
-                    .current()
-                    .contains(&UidMailbox::new_unassigned(src_mailbox.id.mailbox_id))
-                    || mailboxes.current().contains(&dest_mailbox_id)
-                {
-                    continue;
-                }
-
-                // Add destination folder
-                mailboxes.update(dest_mailbox_id, true);
-                if is_move {
-                    mailboxes.update(UidMailbox::new_unassigned(src_mailbox.id.mailbox_id), false);
-                }
-
-                // Assign IMAP UIDs
-                for uid_mailbox in mailboxes.inner_tags_mut() {
-                    if uid_mailbox.uid == 0 {
-                        let assigned_uid = self
-                            .jmap
-                            .assign_imap_uid(account_id, uid_mailbox.mailbox_id)
-                            .await
-                            .imap_ctx(&arguments.tag, trc::location!())?;
-                        debug_assert!(assigned_uid > 0);
-                        copied_ids.push((imap_id.uid, assigned_uid));
-                        uid_mailbox.uid = assigned_uid;
-                    }
-                }
-
-                // Write changes
-                let mut batch = BatchBuilder::new();
-                batch
-                    .with_account_id(account_id)
-                    .with_collection(Collection::Email)
-                    .update_document(id);
-                mailboxes.update_batch(&mut batch, Property::MailboxIds);
-                if changelog.change_id == u64::MAX {
-                    changelog.change_id = self
-                        .jmap
-                        .assign_change_id(account_id)
-                        .await
-                        .imap_ctx(&arguments.tag, trc::location!())?;
-                }
-                batch.value(Property::Cid, changelog.change_id, F_VALUE);
-                self.jmap
-                    .write_batch(batch)
-                    .await
-                    .imap_ctx(&arguments.tag, trc::location!())?;
-                changelog.log_update(Collection::Email, Id::from_parts(thread_id, id));
-                changelog.log_child_update(Collection::Mailbox, dest_mailbox_id.mailbox_id);
-                if is_move {
-                    changelog.log_child_update(Collection::Mailbox, src_mailbox.id.mailbox_id);
-                    did_move = true;
+                    copied_ids.extend(assigned_uids.into_iter().map(|uid| (imap_id.uid, uid)));
+                    did_move |= is_move;
                 }
             }
         } else {
@@ -408,6 +357,76 @@ impl<T: SessionStream> SessionData<T> {
         self.write_bytes(response).await
     }
 
+    // Adds a message to the destination mailbox and removes it from the source mailbox
+    // when moving. Returns the assigned UIDs, or None when the message no longer belongs
+    // to the source mailbox or is already in the destination mailbox.
+    pub async fn copy_move_message(
+        &self,
+        account_id: u32,
+        id: u32,
+        src_mailbox_id: u32,
+        dest_mailbox_id: u32,
+        is_move: bool,
+        changelog: &mut ChangeLogBuilder,
+    ) -> trc::Result<Option<Vec<u32>>> {
+        // Obtain mailbox tags
+        let (mut mailboxes, thread_id) =
+            if let Some(result) = self.get_mailbox_tags(account_id, id).await? {
+                result
+            } else {
+                return Ok(None);
+            };
+
+        // Make sure the message still belongs to this mailbox
+        let src_mailbox_id = UidMailbox::new_unassigned(src_mailbox_id);
+        let dest_mailbox_id = UidMailbox::new_unassigned(dest_mailbox_id);
+        if !mailboxes.current().contains(&src_mailbox_id)
+            || mailboxes.current().contains(&dest_mailbox_id)
+        {
+            return Ok(None);
+        }
+
+        // Add destination folder
+        mailboxes.update(dest_mailbox_id, true);
+        if is_move {
+            mailboxes.update(src_mailbox_id, false);
+        }
+
+        // Assign IMAP UIDs
+        let mut assigned_uids = Vec::with_capacity(1);
+        for uid_mailbox in mailboxes.inner_tags_mut() {
+            if uid_mailbox.uid == 0 {
+                let assigned_uid = self
+                    .jmap
+                    .assign_imap_uid(account_id, uid_mailbox.mailbox_id)
+                    .await?;
+                debug_assert!(assigned_uid > 0);
+                assigned_uids.push(assigned_uid);
+                uid_mailbox.uid = assigned_uid;
+            }
+        }
+
+        // Write changes
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Email)
+            .update_document(id);
+        mailboxes.update_batch(&mut batch, Property::MailboxIds);
+        if changelog.change_id == u64::MAX {
+            changelog.change_id = self.jmap.assign_change_id(account_id).await?;
+        }
+        batch.value(Property::Cid, changelog.change_id, F_VALUE);
+        self.jmap.write_batch(batch).await?;
+        changelog.log_update(Collection::Email, Id::from_parts(thread_id, id));
+        changelog.log_child_update(Collection::Mailbox, dest_mailbox_id.mailbox_id);
+        if is_move {
+            changelog.log_child_update(Collection::Mailbox, src_mailbox_id.mailbox_id);
+        }
+
+        Ok(Some(assigned_uids))
+    }
+
     pub async fn get_mailbox_tags(
         &self,
         account_id: u32,
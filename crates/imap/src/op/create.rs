@@ -69,6 +69,35 @@ impl<T: SessionStream> SessionData<T> {
             .imap_ctx(&arguments.tag, trc::location!())?;
         debug_assert!(!params.path.is_empty());
 
+        // Infer the special use role from the name of top-level mailboxes created without USE
+        let mut mailbox_role = arguments.mailbox_role;
+        if mailbox_role.is_none() && !params.full_path.contains('/') {
+            if let Some(role) = self
+                .jmap
+                .core
+                .imap
+                .folder_roles
+                .get(&params.full_path.to_lowercase())
+                .copied()
+            {
+                if self
+                    .jmap
+                    .filter(
+                        params.account_id,
+                        Collection::Mailbox,
+                        vec![Filter::eq(Property::Role, role)],
+                    )
+                    .await
+                    .imap_ctx(&arguments.tag, trc::location!())?
+                    .results
+                    .is_empty()
+                {
+                    mailbox_role = Some(role);
+                    params.special_use = Attribute::try_from(role).ok();
+                }
+            }
+        }
+
         // Build batch
         let mut changes = self
             .jmap
@@ -87,7 +116,7 @@ impl<T: SessionStream> SessionData<T> {
                     Value::UnsignedInt(rand::random::<u32>() as u64),
                 );
             if pos == params.path.len() - 1 {
-                if let Some(mailbox_role) = arguments.mailbox_role {
+                if let Some(mailbox_role) = mailbox_role {
                     mailbox.set(Property::Role, mailbox_role);
                 }
             }
@@ -284,6 +313,11 @@ impl<T: SessionStream> SessionData<T> {
             path.push(name);
         }
 
+        // INBOX is always matched case-insensitively
+        if path[0].eq_ignore_ascii_case("inbox") {
+            path[0] = "INBOX";
+        }
+
         // Validate special folders
         let full_path = path.join("/");
         let mut parent_mailbox_id = None;
@@ -325,11 +359,28 @@ impl<T: SessionStream> SessionData<T> {
                     .code(ResponseCode::ContactAdmin));
             };
 
-            // Locate parent mailbox
-            if account.mailbox_names.contains_key(&full_path) {
+            // Mailbox names are folded before comparing when configured to do so
+            let case_insensitive = self.jmap.core.imap.folders_case_insensitive;
+            let find_mailbox = |name: &str| {
+                account.mailbox_names.get_key_value(name).or_else(|| {
+                    if case_insensitive {
+                        let name = name.to_lowercase();
+                        account
+                            .mailbox_names
+                            .iter()
+                            .find(|(mailbox_name, _)| mailbox_name.to_lowercase() == name)
+                    } else {
+                        None
+                    }
+                })
+            };
+
+            // Locate parent mailbox, INBOX is matched case-insensitively
+            if find_mailbox(&full_path).is_some() || full_path.eq_ignore_ascii_case("inbox") {
                 return Err(trc::ImapEvent::Error
                     .into_err()
-                    .details(format!("Mailbox '{}' already exists.", full_path)));
+                    .details(format!("Mailbox '{}' already exists.", full_path))
+                    .code(ResponseCode::AlreadyExists));
             }
 
             (
@@ -338,9 +389,9 @@ impl<T: SessionStream> SessionData<T> {
                     let mut create_path = Vec::with_capacity(path.len());
                     while !path.is_empty() {
                         let mailbox_name = path.join("/");
-                        if let Some(&mailbox_id) = account.mailbox_names.get(&mailbox_name) {
+                        if let Some((mailbox_name, &mailbox_id)) = find_mailbox(&mailbox_name) {
                             parent_mailbox_id = mailbox_id.into();
-                            parent_mailbox_name = mailbox_name.into();
+                            parent_mailbox_name = mailbox_name.clone().into();
                             break;
                         } else {
                             create_path.push(path.pop().unwrap());
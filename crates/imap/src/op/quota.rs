@@ -0,0 +1,162 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::time::Instant;
+
+use crate::{
+    core::{Session, SessionData},
+    op::ImapContext,
+    spawn_op,
+};
+use common::listener::SessionStream;
+use imap_proto::{
+    protocol::quota::{Quota, QuotaResource, QuotaRoot, Resource},
+    receiver::Request,
+    Command, ResponseCode, StatusResponse,
+};
+use trc::AddContext;
+
+impl<T: SessionStream> Session<T> {
+    pub async fn handle_get_quota(&mut self, request: Request<Command>) -> trc::Result<()> {
+        let op_start = Instant::now();
+        let arguments = request.parse_get_quota()?;
+        let data = self.state.session_data();
+
+        spawn_op!(data, {
+            let quota = data
+                .get_quota(&arguments.name)
+                .await
+                .imap_ctx(&arguments.tag, trc::location!())?
+                .ok_or_else(|| {
+                    trc::ImapEvent::Error
+                        .into_err()
+                        .details("Quota root does not exist.")
+                        .code(ResponseCode::NonExistent)
+                        .id(arguments.tag.clone())
+                })?;
+
+            trc::event!(
+                Imap(trc::ImapEvent::GetQuota),
+                SpanId = data.session_id,
+                Details = arguments.name,
+                Elapsed = op_start.elapsed()
+            );
+
+            let mut buf = Vec::with_capacity(32);
+            quota.serialize(&mut buf);
+            data.write_bytes(
+                StatusResponse::completed(Command::GetQuota)
+                    .with_tag(arguments.tag)
+                    .serialize(buf),
+            )
+            .await
+        })
+    }
+
+    pub async fn handle_get_quota_root(&mut self, request: Request<Command>) -> trc::Result<()> {
+        let op_start = Instant::now();
+        let arguments = request.parse_get_quota_root(self.version)?;
+        let version = self.version;
+        let data = self.state.session_data();
+
+        spawn_op!(data, {
+            // Refresh mailboxes
+            data.synchronize_mailboxes(false)
+                .await
+                .imap_ctx(&arguments.tag, trc::location!())?;
+
+            // Only mailboxes in the user's own account have a quota root
+            let mailbox = data.get_mailbox_by_name(&arguments.name).ok_or_else(|| {
+                trc::ImapEvent::Error
+                    .into_err()
+                    .details("Mailbox does not exist.")
+                    .code(ResponseCode::NonExistent)
+                    .id(arguments.tag.clone())
+            })?;
+            let quotas = if mailbox.account_id == data.account_id {
+                data.get_quota("")
+                    .await
+                    .imap_ctx(&arguments.tag, trc::location!())?
+                    .into_iter()
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            trc::event!(
+                Imap(trc::ImapEvent::GetQuotaRoot),
+                SpanId = data.session_id,
+                MailboxName = arguments.name.clone(),
+                Elapsed = op_start.elapsed()
+            );
+
+            let mut buf = Vec::with_capacity(64);
+            QuotaRoot {
+                mailbox_name: arguments.name,
+                quotas,
+            }
+            .serialize(&mut buf, version.is_rev2());
+            data.write_bytes(
+                StatusResponse::completed(Command::GetQuotaRoot)
+                    .with_tag(arguments.tag)
+                    .serialize(buf),
+            )
+            .await
+        })
+    }
+
+    pub async fn handle_set_quota(&mut self, request: Request<Command>) -> trc::Result<()> {
+        // Limits are set on the principal by the directory, QUOTASET is not advertised
+        Err(trc::ImapEvent::Error
+            .into_err()
+            .details("Quota limits can only be changed by an administrator.")
+            .code(ResponseCode::NoPerm)
+            .id(request.tag))
+    }
+}
+
+impl<T: SessionStream> SessionData<T> {
+    pub async fn get_quota(&self, root: &str) -> trc::Result<Option<Quota>> {
+        // Quotas are tracked per account, the only quota root is the user's own account
+        if !root.is_empty() {
+            return Ok(None);
+        }
+        let quota = self.get_access_token().await?.quota;
+        if quota == 0 {
+            return Ok(None);
+        }
+
+        // Usage is never reported as negative, a negative counter is logged once per account
+        let used_quota = self
+            .jmap
+            .get_used_quota(self.account_id)
+            .await
+            .caused_by(trc::location!())?;
+        if used_quota < 0 {
+            if self.imap.negative_quota.insert(self.account_id) {
+                trc::event!(
+                    Store(trc::StoreEvent::NegativeQuota),
+                    AccountId = self.account_id,
+                    Value = used_quota,
+                    SpanId = self.session_id
+                );
+            }
+        } else {
+            self.imap.negative_quota.remove(&self.account_id);
+        }
+        let used_quota = used_quota.max(0) as u64;
+
+        // STORAGE is expressed in units of 1024 octets
+        Ok(Some(Quota {
+            root: String::new(),
+            resources: vec![QuotaResource {
+                resource: Resource::Storage,
+                usage: used_quota.div_ceil(1024),
+                limit: quota.div_ceil(1024),
+            }],
+        }))
+    }
+}
@@ -7,14 +7,22 @@
 use std::{collections::BTreeMap, time::Instant};
 
 use crate::{
-    core::{Session, SessionData},
+    core::{message::MAX_RETRIES, Session, SessionData},
+    op::create::CreateParams,
     spawn_op,
 };
+use ahash::AHashMap;
 use common::listener::SessionStream;
 use imap_proto::{
-    protocol::rename::Arguments, receiver::Request, Command, ResponseCode, StatusResponse,
+    protocol::{create, delete, rename::Arguments},
+    receiver::Request,
+    Command, ResponseCode, StatusResponse,
+};
+use jmap::{
+    auth::acl::EffectiveAcl,
+    email::set::TagManager,
+    mailbox::{set::SCHEMA, UidMailbox, INBOX_ID},
 };
-use jmap::{auth::acl::EffectiveAcl, mailbox::set::SCHEMA};
 use jmap_proto::{
     object::{index::ObjectIndexBuilder, Object},
     types::{
@@ -22,11 +30,14 @@ use jmap_proto::{
         type_state::DataType, value::Value,
     },
 };
-use store::write::{assert::HashedValue, BatchBuilder};
+use store::write::{assert::HashedValue, log::ChangeLogBuilder, BatchBuilder, F_VALUE};
 use trc::AddContext;
 
 use super::ImapContext;
 
+// Messages moved per batch when renaming INBOX
+const MAX_BATCH_SIZE: usize = 100;
+
 impl<T: SessionStream> Session<T> {
     pub async fn handle_rename(&mut self, request: Request<Command>) -> trc::Result<()> {
         let op_start = Instant::now();
@@ -58,6 +69,11 @@ impl<T: SessionStream> SessionData<T> {
             .add_context(|err| err.id(arguments.tag.clone()))?;
         params.is_rename = true;
 
+        // Renaming INBOX moves its messages to a new mailbox and leaves INBOX in place
+        if arguments.mailbox_name.eq_ignore_ascii_case("INBOX") {
+            return self.rename_inbox(&arguments, params, op_start).await;
+        }
+
         // Validate source mailbox
         let mailbox_id = {
             let mut mailbox_id = None;
@@ -262,4 +278,226 @@ impl<T: SessionStream> SessionData<T> {
 
         Ok(StatusResponse::completed(Command::Rename).with_tag(arguments.tag))
     }
+
+    async fn rename_inbox(
+        &self,
+        arguments: &Arguments,
+        params: CreateParams<'_>,
+        op_start: Instant,
+    ) -> trc::Result<StatusResponse> {
+        let account_id = self.account_id;
+        if params.account_id != account_id {
+            return Err(trc::ImapEvent::Error
+                .into_err()
+                .details("Cannot move mailboxes between accounts.")
+                .code(ResponseCode::Cannot)
+                .id(arguments.tag.clone()));
+        }
+
+        // Create the destination mailbox
+        self.create_folder(create::Arguments {
+            tag: arguments.tag.clone(),
+            mailbox_name: arguments.new_mailbox_name.clone(),
+            mailbox_role: None,
+        })
+        .await?;
+        let dest_mailbox_id = self
+            .mailboxes
+            .lock()
+            .iter()
+            .find(|account| account.account_id == account_id)
+            .and_then(|account| account.mailbox_names.get(&params.full_path).copied())
+            .ok_or_else(|| {
+                trc::ImapEvent::Error
+                    .into_err()
+                    .details(format!("Mailbox '{}' not found.", params.full_path))
+                    .caused_by(trc::location!())
+                    .code(ResponseCode::NonExistent)
+                    .id(arguments.tag.clone())
+            })?;
+
+        // Move all messages from INBOX to the new mailbox. On failure, the messages
+        // already moved are returned to INBOX and the new mailbox is removed.
+        let mut changelog = ChangeLogBuilder::new();
+        let mut result = self
+            .move_mailbox_messages(account_id, INBOX_ID, dest_mailbox_id, &mut changelog)
+            .await;
+        let mut rolled_back = false;
+        if let Err(err) = result {
+            result = match self
+                .move_mailbox_messages(account_id, dest_mailbox_id, INBOX_ID, &mut changelog)
+                .await
+            {
+                Ok(_) => {
+                    rolled_back = true;
+                    Err(err)
+                }
+                Err(rollback_err) => {
+                    trc::error!(err.span_id(self.session_id));
+                    Err(rollback_err.details(format!(
+                        "Failed to return messages to INBOX from mailbox '{}'.",
+                        params.full_path
+                    )))
+                }
+            };
+        }
+
+        if !changelog.is_empty() {
+            let change_id = self
+                .jmap
+                .commit_changes(account_id, changelog)
+                .await
+                .imap_ctx(&arguments.tag, trc::location!())?;
+            self.jmap
+                .broadcast_state_change(
+                    StateChange::new(account_id)
+                        .with_change(DataType::Email, change_id)
+                        .with_change(DataType::Mailbox, change_id),
+                )
+                .await;
+        }
+
+        if let Err(err) = result {
+            // Do not remove the new mailbox while it still contains messages
+            if rolled_back {
+                if let Err(delete_err) = self
+                    .delete_folder(delete::Arguments {
+                        tag: arguments.tag.clone(),
+                        mailbox_name: arguments.new_mailbox_name.clone(),
+                    })
+                    .await
+                {
+                    trc::error!(err.span_id(self.session_id));
+                    return Err(delete_err);
+                }
+            }
+
+            return Err(err).imap_ctx(&arguments.tag, trc::location!());
+        }
+
+        trc::event!(
+            Imap(trc::ImapEvent::RenameMailbox),
+            SpanId = self.session_id,
+            AccountId = account_id,
+            MailboxName = arguments.new_mailbox_name.clone(),
+            MailboxId = dest_mailbox_id,
+            Elapsed = op_start.elapsed()
+        );
+
+        Ok(StatusResponse::completed(Command::Rename).with_tag(arguments.tag.clone()))
+    }
+
+    // Moves all messages of a mailbox to another mailbox of the same account,
+    // writing up to MAX_BATCH_SIZE messages per batch
+    async fn move_mailbox_messages(
+        &self,
+        account_id: u32,
+        src_mailbox_id: u32,
+        dest_mailbox_id: u32,
+        changelog: &mut ChangeLogBuilder,
+    ) -> trc::Result<()> {
+        let message_ids = self
+            .jmap
+            .get_tag(
+                account_id,
+                Collection::Email,
+                Property::MailboxIds,
+                src_mailbox_id,
+            )
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let src_mailbox_id = UidMailbox::new_unassigned(src_mailbox_id);
+        let dest_mailbox_id = UidMailbox::new_unassigned(dest_mailbox_id);
+
+        for message_ids in message_ids.chunks(MAX_BATCH_SIZE) {
+            let message_ids = message_ids.to_vec();
+            let mut try_count = 0;
+
+            loop {
+                let thread_ids = self
+                    .jmap
+                    .get_properties::<u32, _, _>(
+                        account_id,
+                        Collection::Email,
+                        &message_ids,
+                        Property::ThreadId,
+                    )
+                    .await?
+                    .into_iter()
+                    .collect::<AHashMap<_, _>>();
+                let mut batch = BatchBuilder::new();
+                batch
+                    .with_account_id(account_id)
+                    .with_collection(Collection::Email);
+                let mut moved_ids = Vec::with_capacity(message_ids.len());
+
+                for (id, mailbox_ids) in self
+                    .jmap
+                    .get_properties::<HashedValue<Vec<UidMailbox>>, _, _>(
+                        account_id,
+                        Collection::Email,
+                        &message_ids,
+                        Property::MailboxIds,
+                    )
+                    .await?
+                {
+                    // Skip messages that were moved or deleted in the meantime
+                    let mut mailboxes = TagManager::new(mailbox_ids);
+                    let Some(thread_id) = thread_ids.get(&id).copied() else {
+                        continue;
+                    };
+                    if !mailboxes.current().contains(&src_mailbox_id)
+                        || mailboxes.current().contains(&dest_mailbox_id)
+                    {
+                        continue;
+                    }
+                    mailboxes.update(dest_mailbox_id, true);
+                    mailboxes.update(src_mailbox_id, false);
+
+                    // Assign IMAP UIDs
+                    for uid_mailbox in mailboxes.inner_tags_mut() {
+                        if uid_mailbox.uid == 0 {
+                            uid_mailbox.uid = self
+                                .jmap
+                                .assign_imap_uid(account_id, uid_mailbox.mailbox_id)
+                                .await?;
+                        }
+                    }
+
+                    if changelog.change_id == u64::MAX {
+                        changelog.change_id = self.jmap.assign_change_id(account_id).await?;
+                    }
+                    batch.update_document(id);
+                    mailboxes.update_batch(&mut batch, Property::MailboxIds);
+                    batch.value(Property::Cid, changelog.change_id, F_VALUE);
+                    moved_ids.push(Id::from_parts(thread_id, id));
+                }
+
+                if moved_ids.is_empty() {
+                    break;
+                }
+
+                match self.jmap.write_batch(batch).await {
+                    Ok(_) => {
+                        for id in moved_ids {
+                            changelog.log_update(Collection::Email, id);
+                        }
+                        changelog.log_child_update(Collection::Mailbox, src_mailbox_id.mailbox_id);
+                        changelog.log_child_update(Collection::Mailbox, dest_mailbox_id.mailbox_id);
+                        break;
+                    }
+                    Err(err) if err.is_assertion_failure() && try_count < MAX_RETRIES => {
+                        try_count += 1;
+                    }
+                    Err(err) => {
+                        return Err(err.caused_by(trc::location!()));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -39,7 +39,7 @@ impl<T: SessionStream> Session<T> {
 
         if let Some(mailbox) = data.get_mailbox_by_name(&arguments.mailbox_name) {
             // Try obtaining the mailbox from the cache
-            let state = {
+            let mut state = {
                 let modseq = data
                     .get_modseq(mailbox.account_id)
                     .await
@@ -69,6 +69,20 @@ impl<T: SessionStream> Session<T> {
                 }
             };
 
+            // Make sure the UID counter is not behind the highest UID in use
+            if let Some(uid_validity) = data
+                .check_uid_counter(&mailbox, state.uid_max)
+                .await
+                .imap_ctx(&arguments.tag, trc::location!())?
+            {
+                if uid_validity != state.uid_validity {
+                    state.uid_validity = uid_validity;
+                    self.imap
+                        .cache_mailbox
+                        .insert(mailbox, Arc::new(state.clone()));
+                }
+            }
+
             // Synchronize messages
             let closed_previous = self.state.close_mailbox();
             let is_condstore = self.is_condstore || arguments.condstore;
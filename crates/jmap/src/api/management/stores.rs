@@ -8,6 +8,7 @@ use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use common::manager::webadmin::Resource;
 use directory::backend::internal::manage::{self, ManageDirectory};
 use hyper::Method;
+use jmap_proto::types::{collection::Collection, id::Id};
 use serde_json::json;
 use utils::url_params::UrlParams;
 
@@ -119,6 +120,88 @@ impl JMAP {
                 self.housekeeper_request(Event::Purge(PurgeType::Account(account_id)))
                     .await
             }
+            (Some("purge"), Some("chunks"), id, &Method::GET) => {
+                let store = if let Some(id) = id {
+                    if let Some(store) = self.core.storage.stores.get(id) {
+                        store.clone()
+                    } else {
+                        return Err(trc::ResourceEvent::NotFound.into_err());
+                    }
+                } else {
+                    self.core.storage.data.clone()
+                };
+
+                self.housekeeper_request(Event::Purge(PurgeType::Chunks(store)))
+                    .await
+            }
+            (Some("subscriptions"), Some(account_name), None, &Method::GET) => {
+                let account_id = self
+                    .core
+                    .storage
+                    .data
+                    .get_account_id(decode_path_element(account_name).as_ref())
+                    .await?
+                    .ok_or_else(|| trc::ManageEvent::NotFound.into_err())?;
+
+                Ok(JsonResponse::new(json!({
+                    "data": self.export_subscriptions(account_id).await?,
+                }))
+                .into_http_response())
+            }
+            (Some("subscriptions"), Some(account_name), None, &Method::POST) => {
+                let account_id = self
+                    .core
+                    .storage
+                    .data
+                    .get_account_id(decode_path_element(account_name).as_ref())
+                    .await?
+                    .ok_or_else(|| trc::ManageEvent::NotFound.into_err())?;
+                let names =
+                    serde_json::from_slice::<Vec<String>>(body.as_deref().unwrap_or_default())
+                        .map_err(|err| {
+                            trc::EventType::Resource(trc::ResourceEvent::BadParameters)
+                                .from_json_error(err)
+                        })?;
+
+                Ok(JsonResponse::new(json!({
+                    "data": self.import_subscriptions(account_id, names).await?,
+                }))
+                .into_http_response())
+            }
+            (Some("verify"), Some(account_name), Some(mailbox_id), &Method::GET) => {
+                let account_id = self
+                    .core
+                    .storage
+                    .data
+                    .get_account_id(decode_path_element(account_name).as_ref())
+                    .await?
+                    .ok_or_else(|| trc::ManageEvent::NotFound.into_err())?;
+                let mailbox_id = Id::from_bytes(mailbox_id.as_bytes())
+                    .ok_or_else(|| {
+                        trc::ResourceEvent::BadParameters
+                            .into_err()
+                            .details("Invalid mailbox id")
+                            .ctx(trc::Key::Id, mailbox_id.to_string())
+                    })?
+                    .document_id();
+                if !self
+                    .get_document_ids(account_id, Collection::Mailbox)
+                    .await?
+                    .unwrap_or_default()
+                    .contains(mailbox_id)
+                {
+                    return Err(trc::ManageEvent::NotFound.into_err());
+                }
+                let result = self.verify_mailbox(account_id, mailbox_id).await?;
+
+                Ok(JsonResponse::new(json!({
+                    "data": {
+                        "missingValues": result.missing_values.iter().collect::<Vec<_>>(),
+                        "missingBitmap": result.missing_bitmap.iter().collect::<Vec<_>>(),
+                    },
+                }))
+                .into_http_response())
+            }
             // SPDX-SnippetBegin
             // SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
             // SPDX-License-Identifier: LicenseRef-SEL
@@ -17,6 +17,8 @@ use utils::codec::leb128::{Leb128Iterator, Leb128Vec};
 pub mod get;
 pub mod query;
 pub mod set;
+pub mod subscription;
+pub mod verify;
 
 pub const INBOX_ID: u32 = 0;
 pub const TRASH_ID: u32 = 1;
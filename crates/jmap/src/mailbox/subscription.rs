@@ -0,0 +1,171 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use jmap_proto::{
+    object::{index::ObjectIndexBuilder, Object},
+    types::{
+        collection::Collection, property::Property, state::StateChange, type_state::DataType,
+        value::Value,
+    },
+};
+use store::ahash::AHashMap;
+use store::write::{assert::HashedValue, BatchBuilder};
+use trc::AddContext;
+
+use crate::JMAP;
+
+use super::{
+    set::{MailboxSubscribe, SCHEMA},
+    INBOX_ID,
+};
+
+const MAX_BATCH_SIZE: usize = 100;
+
+impl JMAP {
+    // Returns the IMAP names of all mailboxes the account is subscribed to
+    pub async fn export_subscriptions(&self, account_id: u32) -> trc::Result<Vec<String>> {
+        let mut names = self
+            .mailbox_paths(account_id)
+            .await
+            .caused_by(trc::location!())?
+            .into_iter()
+            .filter_map(|(name, (_, mailbox))| {
+                matches!(mailbox.inner.get(&Property::IsSubscribed), Value::List(ids)
+                    if ids.contains(&Value::Id(account_id.into())))
+                .then_some(name)
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+
+        Ok(names)
+    }
+
+    // Subscribes the account to the named mailboxes, mailboxes that are already
+    // subscribed are left untouched. Returns the names that do not exist.
+    pub async fn import_subscriptions(
+        &self,
+        account_id: u32,
+        names: Vec<String>,
+    ) -> trc::Result<Vec<String>> {
+        // Make sure the default mailboxes exist
+        self.mailbox_get_or_create(account_id)
+            .await
+            .caused_by(trc::location!())?;
+        let mailboxes = self
+            .mailbox_paths(account_id)
+            .await
+            .caused_by(trc::location!())?;
+
+        let mut not_found = Vec::new();
+        let mut updates = Vec::new();
+        for name in names {
+            let path = if name.eq_ignore_ascii_case("INBOX") {
+                "INBOX"
+            } else {
+                name.trim_matches('/')
+            };
+            if let Some((mailbox_id, mailbox)) = mailboxes.get(path) {
+                if !updates.iter().any(|(id, _, _)| id == mailbox_id) {
+                    if let Some(value) = mailbox.inner.mailbox_subscribe(account_id, true) {
+                        updates.push((*mailbox_id, mailbox.clone(), value));
+                    }
+                }
+            } else {
+                not_found.push(name);
+            }
+        }
+
+        // Write changes in bounded batches
+        while !updates.is_empty() {
+            let mut changes = self
+                .begin_changes(account_id)
+                .await
+                .caused_by(trc::location!())?;
+            let mut batch = BatchBuilder::new();
+            batch
+                .with_account_id(account_id)
+                .with_collection(Collection::Mailbox);
+            for (mailbox_id, mailbox, value) in
+                updates.drain(..std::cmp::min(updates.len(), MAX_BATCH_SIZE))
+            {
+                batch.update_document(mailbox_id).custom(
+                    ObjectIndexBuilder::new(SCHEMA)
+                        .with_current(mailbox)
+                        .with_changes(
+                            Object::with_capacity(1).with_property(Property::IsSubscribed, value),
+                        ),
+                );
+                changes.log_update(Collection::Mailbox, mailbox_id);
+            }
+
+            let change_id = changes.change_id;
+            batch.custom(changes);
+            self.write_batch(batch).await.caused_by(trc::location!())?;
+            self.broadcast_state_change(
+                StateChange::new(account_id).with_change(DataType::Mailbox, change_id),
+            )
+            .await;
+        }
+
+        Ok(not_found)
+    }
+
+    // Maps the IMAP name of each mailbox in the account to its id and value
+    async fn mailbox_paths(
+        &self,
+        account_id: u32,
+    ) -> trc::Result<AHashMap<String, (u32, HashedValue<Object<Value>>)>> {
+        let mailbox_ids = self
+            .get_document_ids(account_id, Collection::Mailbox)
+            .await?
+            .unwrap_or_default();
+        let mailboxes = self
+            .get_properties::<HashedValue<Object<Value>>, _, _>(
+                account_id,
+                Collection::Mailbox,
+                &mailbox_ids,
+                Property::Value,
+            )
+            .await?
+            .into_iter()
+            .collect::<AHashMap<_, _>>();
+
+        let mut paths = AHashMap::with_capacity(mailboxes.len());
+        for (&mailbox_id, mailbox) in &mailboxes {
+            let mut path = Vec::new();
+            let mut next_id = Some((mailbox_id, mailbox));
+            while let Some((mailbox_id, mailbox)) = next_id {
+                if mailbox_id == INBOX_ID {
+                    path.push("INBOX");
+                } else {
+                    path.push(
+                        mailbox
+                            .inner
+                            .get(&Property::Name)
+                            .as_string()
+                            .unwrap_or_default(),
+                    );
+                }
+
+                // Parent ids are stored with an offset of one, zero is the root
+                next_id = match mailbox.inner.get(&Property::ParentId) {
+                    Value::Id(parent_id) if parent_id.document_id() > 0 => {
+                        let parent_id = parent_id.document_id() - 1;
+                        mailboxes.get(&parent_id).map(|parent| (parent_id, parent))
+                    }
+                    _ => None,
+                };
+                if path.len() > self.core.jmap.mailbox_max_depth {
+                    break;
+                }
+            }
+            path.reverse();
+            paths.insert(path.join("/"), (mailbox_id, mailbox.clone()));
+        }
+
+        Ok(paths)
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use jmap_proto::types::{collection::Collection, property::Property};
+use store::{
+    roaring::RoaringBitmap,
+    write::{key::DeserializeBigEndian, TagValue, ValueClass},
+    Deserialize, IterateParams, ValueKey, U32_LEN,
+};
+use trc::AddContext;
+
+use crate::JMAP;
+
+use super::UidMailbox;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MailboxConsistency {
+    // Ids present in the mailbox bitmap that have no MailboxIds value
+    pub missing_values: RoaringBitmap,
+    // Ids whose MailboxIds value references the mailbox but are not in its bitmap
+    pub missing_bitmap: RoaringBitmap,
+}
+
+impl JMAP {
+    pub async fn verify_mailbox(
+        &self,
+        account_id: u32,
+        mailbox_id: u32,
+    ) -> trc::Result<MailboxConsistency> {
+        let bitmap = self
+            .get_tag(
+                account_id,
+                Collection::Email,
+                Property::MailboxIds,
+                TagValue::Id(mailbox_id),
+            )
+            .await
+            .caused_by(trc::location!())?
+            .unwrap_or_default();
+
+        // Scan all MailboxIds values in the account rather than fetching them one by one
+        let mut has_value = RoaringBitmap::new();
+        let mut in_mailbox = RoaringBitmap::new();
+        let collection: u8 = Collection::Email.into();
+        let property: u8 = Property::MailboxIds.into();
+        self.core
+            .storage
+            .data
+            .iterate(
+                IterateParams::new(
+                    ValueKey {
+                        account_id,
+                        collection,
+                        document_id: 0,
+                        class: ValueClass::Property(property),
+                    },
+                    ValueKey {
+                        account_id,
+                        collection,
+                        document_id: u32::MAX,
+                        class: ValueClass::Property(property),
+                    },
+                ),
+                |key, value| {
+                    let document_id = key.deserialize_be_u32(key.len() - U32_LEN)?;
+                    has_value.insert(document_id);
+                    if Vec::<UidMailbox>::deserialize(value)?
+                        .iter()
+                        .any(|item| item.mailbox_id == mailbox_id)
+                    {
+                        in_mailbox.insert(document_id);
+                    }
+
+                    Ok(true)
+                },
+            )
+            .await
+            .add_context(|err| {
+                err.caused_by(trc::location!())
+                    .account_id(account_id)
+                    .collection(Collection::Email)
+            })?;
+
+        Ok(MailboxConsistency {
+            missing_values: &bitmap - &has_value,
+            missing_bitmap: in_mailbox - bitmap,
+        })
+    }
+}
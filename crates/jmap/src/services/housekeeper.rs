@@ -43,6 +43,7 @@ pub enum PurgeType {
     Blobs { store: Store, blob_store: BlobStore },
     Lookup(LookupStore),
     Account(Option<u32>),
+    Chunks(Store),
 }
 
 #[derive(PartialEq, Eq)]
@@ -311,6 +312,24 @@ pub fn spawn_housekeeper(core: JmapInstance, mut rx: mpsc::Receiver<Event>) {
                                 }
                             });
                         }
+                        PurgeType::Chunks(store) => {
+                            trc::event!(Housekeeper(HousekeeperEvent::PurgeStore), Type = "chunks");
+
+                            tokio::spawn(async move {
+                                match store.migrate_chunks().await {
+                                    Ok(migrated) => {
+                                        trc::event!(
+                                            Housekeeper(HousekeeperEvent::PurgeStore),
+                                            Type = "chunks",
+                                            Total = migrated
+                                        );
+                                    }
+                                    Err(err) => {
+                                        trc::error!(err.details("Failed to migrate value chunks"));
+                                    }
+                                }
+                            });
+                        }
                         PurgeType::Account(account_id) => {
                             let jmap = JMAP::from(core.clone());
                             tokio::spawn(async move {
@@ -45,6 +45,9 @@ impl<T: SessionStream> Session<T> {
                     needs_literal = size.into();
                     break;
                 }
+                Err(receiver::Error::NonSyncLiteral { .. }) => {
+                    // Only returned when requested, keep reading the literal
+                }
                 Err(receiver::Error::Error { response }) => {
                     if let Err(err) = self.write_error(response).await {
                         trc::error!(err.span_id(self.session_id));
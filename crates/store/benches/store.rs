@@ -0,0 +1,176 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs Ltd <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use store::{
+    write::{BatchBuilder, ValueClass},
+    BitmapKey, IterateParams, Store, ValueKey,
+};
+use tokio::runtime::Runtime;
+use utils::config::Config;
+
+// FoundationDB splits values larger than this into chunks
+const MAX_VALUE_SIZE: usize = 100000;
+
+// Values that fit in a single key, and values split into 2 and 20 chunks
+const VALUE_SIZES: [(&str, usize); 3] = [
+    ("single", MAX_VALUE_SIZE / 2),
+    ("2-chunk", MAX_VALUE_SIZE + MAX_VALUE_SIZE / 2),
+    ("20-chunk", MAX_VALUE_SIZE * 20 - MAX_VALUE_SIZE / 2),
+];
+
+fn bench_rocksdb(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let path = std::env::temp_dir().join(format!("stalwart-bench-{}", std::process::id()));
+    let mut config = Config::new(format!(
+        "[store.bench]\ntype = \"rocksdb\"\npath = \"{}\"\n",
+        path.display()
+    ))
+    .unwrap();
+    let store = Store::from(
+        rt.block_on(store::backend::rocksdb::RocksDbStore::open(
+            &mut config,
+            ("store", "bench"),
+        ))
+        .expect("Failed to open store"),
+    );
+
+    bench_values(c, &rt, &store, "rocksdb/get_value");
+
+    // Small and large bitmaps
+    let mut group = c.benchmark_group("rocksdb/get_bitmap");
+    for (account_id, size) in [(1, 100u32), (2, 100000u32)] {
+        rt.block_on(async {
+            for chunk in (0..size).collect::<Vec<_>>().chunks(10000) {
+                let mut batch = BatchBuilder::new();
+                batch.with_account_id(account_id).with_collection(0);
+                for document_id in chunk {
+                    batch.create_document_with_id(*document_id);
+                }
+                store.write(batch.build()).await.unwrap();
+            }
+        });
+
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.to_async(&rt).iter(|| async {
+                let bitmap = store
+                    .get_bitmap(BitmapKey::document_ids(account_id, 0u8))
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(bitmap.len(), size as u64);
+            })
+        });
+    }
+    group.finish();
+
+    // Range iteration
+    rt.block_on(async {
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(0)
+            .with_collection(0)
+            .update_document(0);
+        for n in 0..10000 {
+            batch.set(
+                ValueClass::Config(format!("bench-range-{n:05}").into_bytes()),
+                format!("value-{n}").into_bytes(),
+            );
+        }
+        store.write(batch.build()).await.unwrap();
+    });
+    c.bench_function("rocksdb/iterate", |b| {
+        b.to_async(&rt).iter(|| async {
+            let mut count = 0;
+            store
+                .iterate(
+                    IterateParams::new(
+                        ValueKey::from(ValueClass::Config(b"bench-range-".to_vec())),
+                        ValueKey::from(ValueClass::Config(b"bench-range-\xFF".to_vec())),
+                    ),
+                    |_, _| {
+                        count += 1;
+                        Ok(true)
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(count, 10000);
+        })
+    });
+
+    drop(store);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+// Reads chunked values back from FoundationDB, which measures their reassembly.
+// Requires a running cluster reachable through the default cluster file.
+#[cfg(feature = "foundation")]
+fn bench_foundationdb(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut config = Config::new("[store.bench]\ntype = \"foundationdb\"\n").unwrap();
+    let store = Store::from(
+        rt.block_on(store::backend::foundationdb::FdbStore::open(
+            &mut config,
+            ("store", "bench"),
+        ))
+        .expect("Failed to open store"),
+    );
+
+    bench_values(c, &rt, &store, "foundationdb/get_value");
+
+    rt.block_on(async {
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(0)
+            .with_collection(0)
+            .update_document(0);
+        for (name, _) in VALUE_SIZES {
+            batch.clear(ValueClass::Config(
+                format!("bench-value-{name}").into_bytes(),
+            ));
+        }
+        store.write(batch.build()).await.unwrap();
+    });
+}
+
+fn bench_values(c: &mut Criterion, rt: &Runtime, store: &Store, group_name: &str) {
+    let mut group = c.benchmark_group(group_name);
+    for (name, size) in VALUE_SIZES {
+        let key = format!("bench-value-{name}").into_bytes();
+        rt.block_on(async {
+            let mut batch = BatchBuilder::new();
+            batch
+                .with_account_id(0)
+                .with_collection(0)
+                .update_document(0)
+                .set(
+                    ValueClass::Config(key.clone()),
+                    "x".repeat(size).into_bytes(),
+                );
+            store.write(batch.build()).await.unwrap();
+        });
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.to_async(rt).iter(|| async {
+                let value = store
+                    .get_value::<String>(ValueKey::from(ValueClass::Config(key.clone())))
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(value.len(), size);
+            })
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "foundation"))]
+criterion_group!(benches, bench_rocksdb);
+#[cfg(feature = "foundation")]
+criterion_group!(benches, bench_rocksdb, bench_foundationdb);
+criterion_main!(benches);
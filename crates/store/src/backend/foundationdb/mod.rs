@@ -8,12 +8,22 @@ use std::time::{Duration, Instant};
 
 use foundationdb::{api::NetworkAutoStop, Database, FdbError, Transaction};
 
+use crate::SUBSPACE_CHUNKS;
+
 pub mod blob;
 pub mod main;
 pub mod read;
 pub mod write;
 
+// Values of MAX_VALUE_SIZE bytes or more are split into chunks. The value key holds
+// the first MAX_VALUE_SIZE - 1 bytes followed by the number of continuation chunks,
+// which are stored in SUBSPACE_CHUNKS under the escaped value key and a chunk number
+// (see chunk_prefix). Values shorter than MAX_VALUE_SIZE are stored as they are.
+// Values without chunks in SUBSPACE_CHUNKS are read in the previous layout, with the
+// chunks stored under the value key followed by the chunk number.
 const MAX_VALUE_SIZE: usize = 100000;
+// Chunk numbers stay below u8::MAX so that prefix||u8::MAX bounds every chunk range
+const MAX_CHUNKS: usize = u8::MAX as usize - 1;
 pub const TRANSACTION_EXPIRY: Duration = Duration::from_secs(1);
 pub const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(4);
 
@@ -75,6 +85,23 @@ impl TimedTransaction {
     }
 }
 
+// Returns SUBSPACE_CHUNKS followed by the value key with each zero byte escaped as
+// 0x00 0xFF and terminated by a zero byte. The encoding is prefix free and keeps the
+// order of value keys, so the chunks of a value never share their prefix with other
+// keys and the chunks of a key range are stored in a single contiguous range.
+pub(crate) fn chunk_prefix(key: &[u8]) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(key.len() + 3);
+    prefix.push(SUBSPACE_CHUNKS);
+    for &byte in key {
+        prefix.push(byte);
+        if byte == 0 {
+            prefix.push(u8::MAX);
+        }
+    }
+    prefix.push(0);
+    prefix
+}
+
 #[inline(always)]
 fn into_error(error: FdbError) -> trc::Error {
     trc::StoreEvent::FoundationdbError
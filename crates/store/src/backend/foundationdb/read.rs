@@ -14,6 +14,7 @@ use roaring::RoaringBitmap;
 
 use crate::{
     backend::deserialize_i64_le,
+    query::is_empty_range,
     write::{
         key::{DeserializeBigEndian, KeySerializer},
         BitmapClass, ValueClass,
@@ -21,7 +22,7 @@ use crate::{
     BitmapKey, Deserialize, IterateParams, Key, ValueKey, U32_LEN, WITH_SUBSPACE,
 };
 
-use super::{into_error, FdbStore, ReadVersion, TimedTransaction, MAX_VALUE_SIZE};
+use super::{chunk_prefix, into_error, FdbStore, ReadVersion, TimedTransaction, MAX_VALUE_SIZE};
 
 #[allow(dead_code)]
 pub(crate) enum ChunkedValue {
@@ -82,9 +83,24 @@ impl FdbStore {
         mut cb: impl for<'x> FnMut(&'x [u8], &'x [u8]) -> trc::Result<bool> + Sync + Send,
     ) -> trc::Result<()> {
         let mut begin = params.begin.serialize(WITH_SUBSPACE);
-        let end = params.end.serialize(WITH_SUBSPACE);
+        let mut end = params.end.serialize(WITH_SUBSPACE);
+        if is_empty_range(&begin[1..], &end[1..], params.end_exclusive) {
+            return Ok(());
+        }
+        let mut limit = params.limit.unwrap_or(usize::MAX);
+        let end_selector = if params.end_exclusive {
+            if end.len() == 1 {
+                // An empty end key extends the range to the end of the subspace
+                end[0] += 1;
+            }
+            KeySelector::first_greater_or_equal(end)
+        } else {
+            KeySelector::first_greater_than(end)
+        };
 
-        if !params.first {
+        if limit == 0 {
+            return Ok(());
+        } else if limit > 1 {
             let mut begin_selector = KeySelector::first_greater_or_equal(&begin);
 
             loop {
@@ -95,7 +111,8 @@ impl FdbStore {
                     let mut values = trx.as_ref().get_ranges(
                         RangeOption {
                             begin: begin_selector,
-                            end: KeySelector::first_greater_than(&end),
+                            end: end_selector.clone(),
+                            limit: params.limit.map(|_| limit),
                             mode: options::StreamingMode::WantAll,
                             reverse: !params.ascending,
                             ..Default::default()
@@ -108,7 +125,10 @@ impl FdbStore {
 
                         for value in values.iter() {
                             last_key = value.key();
-                            if !cb(last_key.get(1..).unwrap_or_default(), value.value())? {
+                            limit -= 1;
+                            if !cb(last_key.get(1..).unwrap_or_default(), value.value())?
+                                || limit == 0
+                            {
                                 return Ok(());
                             }
                         }
@@ -132,7 +152,7 @@ impl FdbStore {
             let mut values = trx.get_ranges_keyvalues(
                 RangeOption {
                     begin: KeySelector::first_greater_or_equal(&begin),
-                    end: KeySelector::first_greater_than(&end),
+                    end: end_selector,
                     mode: options::StreamingMode::Small,
                     reverse: !params.ascending,
                     ..Default::default()
@@ -200,24 +220,82 @@ pub(crate) async fn read_chunked_value(
         if bytes.len() < MAX_VALUE_SIZE {
             Ok(ChunkedValue::Single(bytes))
         } else {
-            let mut value = Vec::with_capacity(bytes.len() * 2);
-            value.extend_from_slice(&bytes);
-            let mut key = KeySerializer::new(key.len() + 1)
-                .write(key)
+            // The last byte of the head is the number of continuation chunks
+            let n_chunks = bytes[bytes.len() - 1];
+            let mut value = Vec::with_capacity(bytes.len() + n_chunks as usize * MAX_VALUE_SIZE);
+            value.extend_from_slice(&bytes[..bytes.len() - 1]);
+            let prefix = chunk_prefix(key);
+            let begin = KeySerializer::new(prefix.len() + 1)
+                .write(prefix.as_slice())
                 .write(0u8)
                 .finalize();
+            let end = KeySerializer::new(prefix.len() + 1)
+                .write(prefix.as_slice())
+                .write(n_chunks)
+                .finalize();
 
-            while let Some(bytes) = trx.get(&key, snapshot).await.map_err(into_error)? {
-                value.extend_from_slice(&bytes);
-                *key.last_mut().unwrap() += 1;
+            // Fetch all continuation chunks in a single range read
+            let mut values = trx.get_ranges_keyvalues(
+                RangeOption {
+                    begin: KeySelector::first_greater_or_equal(begin),
+                    end: KeySelector::first_greater_or_equal(end),
+                    mode: StreamingMode::WantAll,
+                    reverse: false,
+                    ..RangeOption::default()
+                },
+                snapshot,
+            );
+            let mut chunk_id = 0u8;
+            let mut has_chunks = false;
+            while let Some(chunk) = values.try_next().await.map_err(into_error)? {
+                has_chunks = true;
+                if chunk.key().last() == Some(&chunk_id) {
+                    value.extend_from_slice(chunk.value());
+                    chunk_id += 1;
+                } else {
+                    break;
+                }
             }
 
-            Ok(ChunkedValue::Chunked {
-                bytes: value,
-                n_chunks: *key.last().unwrap(),
-            })
+            if !has_chunks {
+                read_legacy_chunks(key, &bytes, trx, snapshot)
+                    .await
+                    .map(|(bytes, n_chunks)| ChunkedValue::Chunked { bytes, n_chunks })
+            } else if chunk_id == n_chunks {
+                Ok(ChunkedValue::Chunked {
+                    bytes: value,
+                    n_chunks,
+                })
+            } else {
+                Err(trc::Error::corrupted_key(key, None, trc::location!())
+                    .details(format!("Missing value chunk {chunk_id} of {n_chunks}")))
+            }
         }
     } else {
         Ok(ChunkedValue::None)
     }
 }
+
+// Values written before chunks were moved to SUBSPACE_CHUNKS keep the whole head in
+// the value key and their chunks under the value key followed by the chunk number,
+// the first missing number ends the value.
+pub(crate) async fn read_legacy_chunks(
+    key: &[u8],
+    head: &[u8],
+    trx: &Transaction,
+    snapshot: bool,
+) -> trc::Result<(Vec<u8>, u8)> {
+    let mut value = Vec::with_capacity(head.len() * 2);
+    value.extend_from_slice(head);
+    let mut key = KeySerializer::new(key.len() + 1)
+        .write(key)
+        .write(0u8)
+        .finalize();
+
+    while let Some(bytes) = trx.get(&key, snapshot).await.map_err(into_error)? {
+        value.extend_from_slice(&bytes);
+        *key.last_mut().unwrap() += 1;
+    }
+
+    Ok((value, *key.last().unwrap()))
+}
@@ -4,10 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use std::{
-    cmp::Ordering,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
 use foundationdb::{
     options::{self, MutationType, StreamingMode},
@@ -24,15 +21,31 @@ use crate::{
         AssignedIds, Batch, BitmapClass, Operation, RandomAvailableId, ValueOp,
         MAX_COMMIT_ATTEMPTS, MAX_COMMIT_TIME,
     },
-    BitmapKey, IndexKey, Key, LogKey, SUBSPACE_COUNTER, SUBSPACE_QUOTA, U32_LEN, WITH_SUBSPACE,
+    BitmapKey, IndexKey, Key, LogKey, SUBSPACE_BITMAP_ID, SUBSPACE_BITMAP_TAG,
+    SUBSPACE_BITMAP_TEXT, SUBSPACE_BLOBS, SUBSPACE_CHUNKS, SUBSPACE_COUNTER, SUBSPACE_INDEXES,
+    SUBSPACE_QUOTA, U32_LEN, WITH_SUBSPACE,
 };
 
 use super::{
-    into_error,
-    read::{read_chunked_value, ChunkedValue},
-    FdbStore, ReadVersion, MAX_VALUE_SIZE,
+    chunk_prefix, into_error,
+    read::{read_chunked_value, read_legacy_chunks, ChunkedValue},
+    FdbStore, ReadVersion, MAX_CHUNKS, MAX_VALUE_SIZE,
 };
 
+// Values are up to MAX_VALUE_SIZE bytes, keep migration transactions small
+const MIGRATE_BATCH_SIZE: usize = 32;
+// Subspaces that never contain chunked values
+const NO_CHUNKS: [u8; 8] = [
+    SUBSPACE_CHUNKS,
+    SUBSPACE_BLOBS,
+    SUBSPACE_COUNTER,
+    SUBSPACE_QUOTA,
+    SUBSPACE_BITMAP_ID,
+    SUBSPACE_BITMAP_TAG,
+    SUBSPACE_BITMAP_TEXT,
+    SUBSPACE_INDEXES,
+];
+
 impl FdbStore {
     pub(crate) async fn write(&self, batch: Batch) -> trc::Result<AssignedIds> {
         let start = Instant::now();
@@ -70,7 +83,7 @@ impl FdbStore {
                         change_id = *change_id_;
                     }
                     Operation::Value { class, op } => {
-                        let mut key = class.serialize(
+                        let key = class.serialize(
                             account_id,
                             collection,
                             document_id,
@@ -82,27 +95,10 @@ impl FdbStore {
                         match op {
                             ValueOp::Set(value) => {
                                 let value = value.resolve(&result)?;
-                                if !value.is_empty() && do_chunk {
-                                    for (pos, chunk) in value.chunks(MAX_VALUE_SIZE).enumerate() {
-                                        match pos.cmp(&1) {
-                                            Ordering::Less => {}
-                                            Ordering::Equal => {
-                                                key.push(0);
-                                            }
-                                            Ordering::Greater => {
-                                                if pos < u8::MAX as usize {
-                                                    *key.last_mut().unwrap() += 1;
-                                                } else {
-                                                    trx.cancel();
-                                                    return Err(trc::StoreEvent::FoundationdbError
-                                                        .ctx(
-                                                            trc::Key::Reason,
-                                                            "Value is too large",
-                                                        ));
-                                                }
-                                            }
-                                        }
-                                        trx.set(&key, chunk);
+                                if do_chunk {
+                                    if let Err(err) = set_chunked(&trx, &key, value.as_ref()) {
+                                        trx.cancel();
+                                        return Err(err);
                                     }
                                 } else {
                                     trx.set(&key, value.as_ref());
@@ -123,16 +119,9 @@ impl FdbStore {
                                 result.push_counter_id(num);
                             }
                             ValueOp::Clear => {
+                                trx.clear(&key);
                                 if do_chunk {
-                                    trx.clear_range(
-                                        &key,
-                                        &KeySerializer::new(key.len() + 1)
-                                            .write(key.as_slice())
-                                            .write(u8::MAX)
-                                            .finalize(),
-                                    );
-                                } else {
-                                    trx.clear(&key);
+                                    clear_chunks(&trx, &chunk_prefix(&key), 0);
                                 }
                             }
                         }
@@ -352,12 +341,146 @@ impl FdbStore {
         Ok(())
     }
 
+    // Rewrites the values stored in the previous chunk layout, returns the number of
+    // values migrated
+    pub(crate) async fn migrate_chunks(&self) -> trc::Result<usize> {
+        let mut migrated = 0;
+
+        for subspace in 0..u8::MAX {
+            if NO_CHUNKS.contains(&subspace) {
+                continue;
+            }
+            let mut begin = KeySelector::first_greater_or_equal(vec![subspace]);
+            let end = KeySelector::first_greater_or_equal(vec![subspace + 1]);
+
+            loop {
+                let mut retry_count = 0;
+                let (last_key, has_more) = loop {
+                    let trx = self.db.create_trx().map_err(into_error)?;
+                    let mut values = Vec::with_capacity(MIGRATE_BATCH_SIZE);
+                    let mut range = trx.get_ranges_keyvalues(
+                        RangeOption {
+                            begin: begin.clone(),
+                            end: end.clone(),
+                            limit: Some(MIGRATE_BATCH_SIZE),
+                            mode: options::StreamingMode::WantAll,
+                            reverse: false,
+                            ..Default::default()
+                        },
+                        false,
+                    );
+                    while let Some(value) = range.try_next().await.map_err(into_error)? {
+                        values.push((value.key().to_vec(), value.value().to_vec()));
+                    }
+                    drop(range);
+
+                    // Rewrite the first value without chunks in SUBSPACE_CHUNKS in its own
+                    // transaction. Reads are not snapshots, a concurrent write fails the commit.
+                    let mut legacy_pos = None;
+                    for (pos, (key, head)) in values.iter().enumerate() {
+                        if head.len() >= MAX_VALUE_SIZE {
+                            let mut chunk_key = chunk_prefix(key);
+                            chunk_key.push(0);
+                            if trx
+                                .get(&chunk_key, false)
+                                .await
+                                .map_err(into_error)?
+                                .is_none()
+                            {
+                                legacy_pos = Some(pos);
+                                break;
+                            }
+                        }
+                    }
+                    let Some(pos) = legacy_pos else {
+                        let has_more = values.len() == MIGRATE_BATCH_SIZE;
+                        break (values.pop().map(|(key, _)| key), has_more);
+                    };
+                    let (key, head) = &values[pos];
+                    let (value, n_chunks) = read_legacy_chunks(key, head, &trx, false).await?;
+                    set_chunked(&trx, key, &value)?;
+                    let mut legacy_key = KeySerializer::new(key.len() + 1)
+                        .write(key.as_slice())
+                        .write(0u8)
+                        .finalize();
+                    for chunk_id in 0..n_chunks {
+                        *legacy_key.last_mut().unwrap() = chunk_id;
+                        trx.clear(&legacy_key);
+                    }
+                    let last_key = key.clone();
+
+                    if self.commit(trx, retry_count < MAX_COMMIT_ATTEMPTS).await? {
+                        migrated += 1;
+                        break (Some(last_key), true);
+                    } else {
+                        retry_count += 1;
+                    }
+                };
+
+                match last_key {
+                    Some(last_key) if has_more => {
+                        begin = KeySelector::first_greater_than(last_key);
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        Ok(migrated)
+    }
+
     pub(crate) async fn delete_range(&self, from: impl Key, to: impl Key) -> trc::Result<()> {
         let from = from.serialize(WITH_SUBSPACE);
         let to = to.serialize(WITH_SUBSPACE);
 
         let trx = self.db.create_trx().map_err(into_error)?;
         trx.clear_range(&from, &to);
+        trx.clear_range(&chunk_prefix(&from), &chunk_prefix(&to));
         self.commit(trx, false).await.map(|_| ())
     }
 }
+
+// Stores the value with its chunks and removes the chunks of a larger previous value
+fn set_chunked(trx: &Transaction, key: &[u8], value: &[u8]) -> trc::Result<()> {
+    let prefix = chunk_prefix(key);
+    let n_chunks = if value.len() < MAX_VALUE_SIZE {
+        trx.set(key, value);
+        0
+    } else {
+        let (head, chunks) = value.split_at(MAX_VALUE_SIZE - 1);
+        let n_chunks = chunks.len().div_ceil(MAX_VALUE_SIZE);
+        if n_chunks > MAX_CHUNKS {
+            return Err(
+                trc::StoreEvent::FoundationdbError.ctx(trc::Key::Reason, "Value is too large")
+            );
+        }
+
+        let mut head_value = Vec::with_capacity(MAX_VALUE_SIZE);
+        head_value.extend_from_slice(head);
+        head_value.push(n_chunks as u8);
+        trx.set(key, &head_value);
+
+        let mut chunk_key = prefix.clone();
+        chunk_key.push(0);
+        for (chunk_id, chunk) in chunks.chunks(MAX_VALUE_SIZE).enumerate() {
+            *chunk_key.last_mut().unwrap() = chunk_id as u8;
+            trx.set(&chunk_key, chunk);
+        }
+        n_chunks as u8
+    };
+    clear_chunks(trx, &prefix, n_chunks);
+
+    Ok(())
+}
+
+fn clear_chunks(trx: &Transaction, prefix: &[u8], from_chunk: u8) {
+    let begin = KeySerializer::new(prefix.len() + 1)
+        .write(prefix)
+        .write(from_chunk)
+        .finalize();
+    let end = KeySerializer::new(prefix.len() + 1)
+        .write(prefix)
+        .write(u8::MAX)
+        .finalize();
+    trx.clear_range(&begin, &end);
+}
@@ -30,7 +30,28 @@ pub const MAX_TOKEN_MASK: usize = MAX_TOKEN_LENGTH - 1;
 
 #[allow(dead_code)]
 fn deserialize_i64_le(key: &[u8], bytes: &[u8]) -> trc::Result<i64> {
-    Ok(i64::from_le_bytes(bytes[..].try_into().map_err(|_| {
+    // An empty counter is treated as zero, any other length than 8 bytes is corruption
+    if bytes.is_empty() {
+        return Ok(0);
+    }
+    Ok(i64::from_le_bytes(bytes.try_into().map_err(|_| {
         trc::Error::corrupted_key(key, bytes.into(), trc::location!())
+            .ctx(trc::Key::Size, bytes.len())
     })?))
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn deserialize_i64_le() {
+        assert_eq!(super::deserialize_i64_le(b"key", &[]).unwrap(), 0);
+        assert_eq!(
+            super::deserialize_i64_le(b"key", &(-12345i64).to_le_bytes()).unwrap(),
+            -12345
+        );
+
+        let err = super::deserialize_i64_le(b"key", &[1, 2, 3, 4]).unwrap_err();
+        assert!(err.matches(trc::EventType::Store(trc::StoreEvent::DataCorruption)));
+        assert_eq!(err.value_as_uint(trc::Key::Size), Some(4));
+    }
+}
@@ -5,10 +5,11 @@
  */
 
 use futures::TryStreamExt;
-use mysql_async::{prelude::Queryable, Row};
+use mysql_async::{prelude::Queryable, Params, Row};
 use roaring::RoaringBitmap;
 
 use crate::{
+    query::is_empty_range,
     write::{key::DeserializeBigEndian, BitmapClass, ValueClass},
     BitmapKey, Deserialize, IterateParams, Key, ValueKey, U32_LEN,
 };
@@ -79,31 +80,32 @@ impl MysqlStore {
         let table = char::from(params.begin.subspace());
         let begin = params.begin.serialize(0);
         let end = params.end.serialize(0);
+        if is_empty_range(&begin, &end, params.end_exclusive) {
+            return Ok(());
+        }
         let keys = if params.values { "k, v" } else { "k" };
+        let order = if params.ascending { "ASC" } else { "DESC" };
+        // The largest limit returns all rows
+        let limit = params.limit.map_or(u64::MAX, |limit| limit as u64);
 
-        let s = conn
-            .prep(&match (params.first, params.ascending) {
-                (true, true) => {
-                    format!(
-                        "SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k ASC LIMIT 1"
-                    )
-                }
-                (true, false) => {
-                    format!(
-                    "SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k DESC LIMIT 1"
-                )
-                }
-                (false, true) => {
-                    format!("SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k ASC")
-                }
-                (false, false) => {
-                    format!("SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k DESC")
-                }
-            })
-            .await
-            .map_err(into_error)?;
+        let (query, args) = if !params.end_exclusive || !end.is_empty() {
+            let end_op = if params.end_exclusive { "<" } else { "<=" };
+            (
+                format!(
+                    "SELECT {keys} FROM {table} WHERE k >= ? AND k {end_op} ? ORDER BY k {order} LIMIT ?"
+                ),
+                Params::Positional(vec![begin.into(), end.into(), limit.into()]),
+            )
+        } else {
+            // An empty exclusive end key extends the range to the end of the subspace
+            (
+                format!("SELECT {keys} FROM {table} WHERE k >= ? ORDER BY k {order} LIMIT ?"),
+                Params::Positional(vec![begin.into(), limit.into()]),
+            )
+        };
+        let s = conn.prep(&query).await.map_err(into_error)?;
         let mut rows = conn
-            .exec_stream::<Row, _, _>(&s, (begin, end))
+            .exec_stream::<Row, _, _>(&s, args)
             .await
             .map_err(into_error)?;
 
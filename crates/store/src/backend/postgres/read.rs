@@ -6,8 +6,10 @@
 
 use futures::{pin_mut, TryStreamExt};
 use roaring::RoaringBitmap;
+use tokio_postgres::types::ToSql;
 
 use crate::{
+    query::is_empty_range,
     write::{key::DeserializeBigEndian, BitmapClass, ValueClass},
     BitmapKey, Deserialize, IterateParams, Key, ValueKey, U32_LEN,
 };
@@ -81,32 +83,49 @@ impl PostgresStore {
         let table = char::from(params.begin.subspace());
         let begin = params.begin.serialize(0);
         let end = params.end.serialize(0);
+        if is_empty_range(&begin, &end, params.end_exclusive) {
+            return Ok(());
+        }
         let keys = if params.values { "k, v" } else { "k" };
-
-        let s = conn
-            .prepare_cached(&match (params.first, params.ascending) {
-                (true, true) => {
-                    format!(
-                        "SELECT {keys} FROM {table} WHERE k >= $1 AND k <= $2 ORDER BY k ASC LIMIT 1"
-                    )
-                }
-                (true, false) => {
-                    format!(
-                    "SELECT {keys} FROM {table} WHERE k >= $1 AND k <= $2 ORDER BY k DESC LIMIT 1"
-                )
-                }
-                (false, true) => {
-                    format!("SELECT {keys} FROM {table} WHERE k >= $1 AND k <= $2 ORDER BY k ASC")
-                }
-                (false, false) => {
-                    format!("SELECT {keys} FROM {table} WHERE k >= $1 AND k <= $2 ORDER BY k DESC")
-                }
-            })
-            .await.map_err(into_error)?;
-        let rows = conn
-            .query_raw(&s, &[&begin, &end])
+        let order = if params.ascending { "ASC" } else { "DESC" };
+        // A NULL limit returns all rows
+        let limit = params.limit.map(|limit| limit as i64);
+
+        let rows = if !params.end_exclusive || !end.is_empty() {
+            let end_op = if params.end_exclusive { "<" } else { "<=" };
+            let s = conn
+                .prepare_cached(&format!(
+                    "SELECT {keys} FROM {table} WHERE k >= $1 AND k {end_op} $2 ORDER BY k {order} LIMIT $3"
+                ))
+                .await
+                .map_err(into_error)?;
+            conn.query_raw(
+                &s,
+                [
+                    &begin as &(dyn ToSql + Sync),
+                    &end as &(dyn ToSql + Sync),
+                    &limit as &(dyn ToSql + Sync),
+                ],
+            )
             .await
-            .map_err(into_error)?;
+        } else {
+            // An empty exclusive end key extends the range to the end of the subspace
+            let s = conn
+                .prepare_cached(&format!(
+                    "SELECT {keys} FROM {table} WHERE k >= $1 ORDER BY k {order} LIMIT $2"
+                ))
+                .await
+                .map_err(into_error)?;
+            conn.query_raw(
+                &s,
+                [
+                    &begin as &(dyn ToSql + Sync),
+                    &limit as &(dyn ToSql + Sync),
+                ],
+            )
+            .await
+        }
+        .map_err(into_error)?;
 
         pin_mut!(rows);
 
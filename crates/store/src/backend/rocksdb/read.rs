@@ -10,7 +10,8 @@ use rocksdb::{Direction, IteratorMode};
 use super::{into_error, RocksDbStore};
 
 use crate::{
-    backend::rocksdb::CfHandle,
+    backend::{deserialize_i64_le, rocksdb::CfHandle},
+    query::is_empty_range,
     write::{key::DeserializeBigEndian, BitmapClass, ValueClass},
     BitmapKey, Deserialize, IterateParams, Key, ValueKey, U32_LEN,
 };
@@ -80,18 +81,34 @@ impl RocksDbStore {
             let cf = db.subspace_handle(params.begin.subspace());
             let begin = params.begin.serialize(0);
             let end = params.end.serialize(0);
+            if is_empty_range(&begin, &end, params.end_exclusive) {
+                return Ok(());
+            }
+            // An empty exclusive end key extends the range to the end of the subspace
+            let is_unbounded = params.end_exclusive && end.is_empty();
             let it_mode = if params.ascending {
                 IteratorMode::From(&begin, Direction::Forward)
-            } else {
+            } else if !is_unbounded {
                 IteratorMode::From(&end, Direction::Reverse)
+            } else {
+                IteratorMode::End
             };
 
+            let mut limit = params.limit.unwrap_or(usize::MAX);
+            if limit == 0 {
+                return Ok(());
+            }
+
             for row in db.iterator_cf(&cf, it_mode) {
                 let (key, value) = row.map_err(into_error)?;
+                if params.end_exclusive && key.as_ref() == end.as_slice() {
+                    continue;
+                }
+                limit -= 1;
                 if key.as_ref() < begin.as_slice()
-                    || key.as_ref() > end.as_slice()
+                    || (!is_unbounded && key.as_ref() > end.as_slice())
                     || !cb(&key, &value)?
-                    || params.first
+                    || limit == 0
                 {
                     break;
                 }
@@ -116,9 +133,7 @@ impl RocksDbStore {
                 .map_err(into_error)
                 .and_then(|bytes| {
                     Ok(if let Some(bytes) = bytes {
-                        i64::from_le_bytes(bytes[..].try_into().map_err(|_| {
-                            trc::Error::corrupted_key(&key, (&bytes[..]).into(), trc::location!())
-                        })?)
+                        deserialize_i64_le(&key, &bytes)?
                     } else {
                         0
                     })
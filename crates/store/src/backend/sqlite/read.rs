@@ -8,6 +8,7 @@ use roaring::RoaringBitmap;
 use rusqlite::OptionalExtension;
 
 use crate::{
+    query::is_empty_range,
     write::{key::DeserializeBigEndian, BitmapClass, ValueClass},
     BitmapKey, Deserialize, IterateParams, Key, ValueKey, U32_LEN,
 };
@@ -83,31 +84,33 @@ impl SqliteStore {
             let table = char::from(params.begin.subspace());
             let begin = params.begin.serialize(0);
             let end = params.end.serialize(0);
+            if is_empty_range(&begin, &end, params.end_exclusive) {
+                return Ok(());
+            }
             let keys = if params.values { "k, v" } else { "k" };
+            let order = if params.ascending { "ASC" } else { "DESC" };
+            // A negative limit returns all rows
+            let limit = params.limit.map_or(-1, |limit| limit as i64);
 
-            let mut query = conn
-                .prepare_cached(&match (params.first, params.ascending) {
-                    (true, true) => {
-                        format!(
-                        "SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k ASC LIMIT 1"
-                    )
-                    }
-                    (true, false) => {
-                        format!(
-                        "SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k DESC LIMIT 1"
-                    )
-                    }
-                    (false, true) => {
-                        format!("SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k ASC")
-                    }
-                    (false, false) => {
-                        format!(
-                            "SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k DESC"
-                        )
-                    }
-                })
-                .map_err(into_error)?;
-            let mut rows = query.query([&begin, &end]).map_err(into_error)?;
+            let mut query;
+            let mut rows = if !params.end_exclusive || !end.is_empty() {
+                let end_op = if params.end_exclusive { "<" } else { "<=" };
+                query = conn
+                    .prepare_cached(&format!(
+                        "SELECT {keys} FROM {table} WHERE k >= ? AND k {end_op} ? ORDER BY k {order} LIMIT ?"
+                    ))
+                    .map_err(into_error)?;
+                query.query(rusqlite::params![begin, end, limit])
+            } else {
+                // An empty exclusive end key extends the range to the end of the subspace
+                query = conn
+                    .prepare_cached(&format!(
+                        "SELECT {keys} FROM {table} WHERE k >= ? ORDER BY k {order} LIMIT ?"
+                    ))
+                    .map_err(into_error)?;
+                query.query(rusqlite::params![begin, limit])
+            }
+            .map_err(into_error)?;
 
             if params.values {
                 while let Some(row) = rows.next().map_err(into_error)? {
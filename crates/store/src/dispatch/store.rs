@@ -14,9 +14,10 @@ use trc::{AddContext, StoreEvent};
 
 use crate::{
     write::{
+        assert::AssertValue,
         key::{DeserializeBigEndian, KeySerializer},
         now, AnyClass, AnyKey, AssignedIds, Batch, BatchBuilder, BitmapClass, BitmapHash,
-        Operation, ReportClass, ValueClass, ValueOp,
+        MaybeDynamicId, Operation, ReportClass, ValueClass, ValueOp,
     },
     BitmapKey, Deserialize, IterateParams, Key, Store, ValueKey, SUBSPACE_BITMAP_ID,
     SUBSPACE_BITMAP_TAG, SUBSPACE_BITMAP_TEXT, SUBSPACE_INDEXES, SUBSPACE_LOGS, U32_LEN,
@@ -107,6 +108,7 @@ impl Store {
         cb: impl for<'x> FnMut(&'x [u8], &'x [u8]) -> trc::Result<bool> + Sync + Send,
     ) -> trc::Result<()> {
         let start_time = Instant::now();
+
         let result = match self {
             #[cfg(feature = "sqlite")]
             Self::SQLite(store) => store.iterate(params, cb).await,
@@ -273,6 +275,42 @@ impl Store {
         result
     }
 
+    // Replaces a value only if its current contents match `expected`, where `None`
+    // means the value must not exist. Setting `new` to `None` deletes the value.
+    pub async fn compare_and_swap_value(
+        &self,
+        account_id: u32,
+        collection: u8,
+        document_id: u32,
+        class: impl Into<ValueClass<MaybeDynamicId>>,
+        expected: Option<&[u8]>,
+        new: Option<&[u8]>,
+    ) -> trc::Result<bool> {
+        let class = class.into();
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account_id)
+            .with_collection(collection)
+            .update_document(document_id)
+            .assert_value(
+                class.clone(),
+                expected.map_or(AssertValue::None, |bytes| {
+                    AssertValue::Hash(xxhash_rust::xxh3::xxh3_64(bytes))
+                }),
+            );
+        if let Some(new) = new {
+            batch.set(class, new.to_vec());
+        } else {
+            batch.clear(class);
+        }
+
+        match self.write(batch.build()).await {
+            Ok(_) => Ok(true),
+            Err(err) if err.is_assertion_failure() => Ok(false),
+            Err(err) => Err(err.caused_by(trc::location!())),
+        }
+    }
+
     pub async fn purge_store(&self) -> trc::Result<()> {
         // Delete expired reports
         let now = now();
@@ -341,6 +379,18 @@ impl Store {
         .caused_by(trc::location!())
     }
 
+    #[allow(unreachable_patterns)]
+    pub async fn migrate_chunks(&self) -> trc::Result<usize> {
+        match self {
+            #[cfg(feature = "foundation")]
+            Self::FoundationDb(store) => store.migrate_chunks().await,
+            Self::None => Err(trc::StoreEvent::NotConfigured.into()),
+            // Other backends do not split values into chunks
+            _ => Ok(0),
+        }
+        .caused_by(trc::location!())
+    }
+
     pub async fn delete_documents(
         &self,
         subspace: u8,
@@ -151,16 +151,18 @@ pub const SUBSPACE_TELEMETRY_SPAN: u8 = b'o';
 pub const SUBSPACE_TELEMETRY_INDEX: u8 = b'w';
 pub const SUBSPACE_TELEMETRY_METRIC: u8 = b'x';
 
-pub const SUBSPACE_RESERVED_1: u8 = b'y';
+// Continuation chunks of large values, only used by FoundationDB
+pub const SUBSPACE_CHUNKS: u8 = b'y';
 pub const SUBSPACE_RESERVED_2: u8 = b'z';
 
 #[derive(Clone)]
 pub struct IterateParams<T: Key> {
     begin: T,
     end: T,
-    first: bool,
+    limit: Option<usize>,
     ascending: bool,
     values: bool,
+    end_exclusive: bool,
 }
 
 #[derive(Clone, Default)]
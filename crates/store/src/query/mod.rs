@@ -12,7 +12,7 @@ pub mod sort;
 use roaring::RoaringBitmap;
 
 use crate::{
-    write::{BitmapClass, BitmapHash, TagValue},
+    write::{AnyKey, BitmapClass, BitmapHash, TagValue},
     BitmapKey, IterateParams, Key, Serialize,
 };
 
@@ -232,9 +232,10 @@ impl<T: Key> IterateParams<T> {
         IterateParams {
             begin,
             end,
-            first: false,
+            limit: None,
             ascending: true,
             values: true,
+            end_exclusive: false,
         }
     }
 
@@ -259,7 +260,12 @@ impl<T: Key> IterateParams<T> {
     }
 
     pub fn only_first(mut self) -> Self {
-        self.first = true;
+        self.limit = Some(1);
+        self
+    }
+
+    pub fn set_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
         self
     }
 
@@ -268,3 +274,48 @@ impl<T: Key> IterateParams<T> {
         self
     }
 }
+
+// Returns true when a serialized key range holds no keys, such ranges are not sent
+// to the backend. An empty end key extends the range to the end of the subspace and
+// an inclusive range with the same begin and end keys holds that key.
+pub(crate) fn is_empty_range(begin: &[u8], end: &[u8], end_exclusive: bool) -> bool {
+    if !end.is_empty() && (begin > end || (begin == end && end_exclusive)) {
+        trc::event!(
+            Store(trc::StoreEvent::DataIterate),
+            Details = "Empty key range"
+        );
+        true
+    } else {
+        false
+    }
+}
+
+impl IterateParams<AnyKey<Vec<u8>>> {
+    pub fn prefix(key: impl Key) -> Self {
+        let subspace = key.subspace();
+        let begin = key.serialize(0);
+
+        // Increment the last byte below 0xFF, dropping the 0xFF bytes that follow it.
+        // A prefix made only of 0xFF bytes leaves an empty end key, which
+        // extends the range to the end of the subspace.
+        let mut end = begin.clone();
+        while let Some(byte) = end.pop() {
+            if byte != u8::MAX {
+                end.push(byte + 1);
+                break;
+            }
+        }
+
+        IterateParams {
+            begin: AnyKey {
+                subspace,
+                key: begin,
+            },
+            end: AnyKey { subspace, key: end },
+            limit: None,
+            ascending: true,
+            values: true,
+            end_exclusive: true,
+        }
+    }
+}
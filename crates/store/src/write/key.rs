@@ -676,3 +676,71 @@ impl Deserialize for ReportEvent {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Deserialize, Serialize, U32_LEN, U64_LEN};
+
+    use super::{DeserializeBigEndian, KeySerializer};
+
+    #[test]
+    fn key_serializer_roundtrip() {
+        let mut values_u32 = vec![0, 1, u8::MAX as u32, u32::MAX - 1, u32::MAX];
+        let mut values_u64 = vec![0, 1, u32::MAX as u64, u64::MAX - 1, u64::MAX];
+        for _ in 0..1000 {
+            values_u32.push(rand::random());
+            values_u64.push(rand::random());
+        }
+
+        for (pos, (value_u32, value_u64)) in values_u32.into_iter().zip(values_u64).enumerate() {
+            // Variable length bytes before and after the integers
+            let prefix = vec![u8::MAX; pos % 7];
+            let suffix = vec![0u8; pos % 3];
+            let key = KeySerializer::new(prefix.len() + U32_LEN + U64_LEN + suffix.len())
+                .write(prefix.as_slice())
+                .write(value_u32)
+                .write(value_u64)
+                .write(suffix.as_slice())
+                .finalize();
+            let key = key.as_slice();
+
+            assert_eq!(key.len(), prefix.len() + U32_LEN + U64_LEN + suffix.len());
+            assert_eq!(&key[..prefix.len()], prefix.as_slice());
+            assert_eq!(key.deserialize_be_u32(prefix.len()).unwrap(), value_u32);
+            assert_eq!(
+                key.deserialize_be_u64(prefix.len() + U32_LEN).unwrap(),
+                value_u64
+            );
+            assert_eq!(&key[prefix.len() + U32_LEN + U64_LEN..], suffix.as_slice());
+
+            // Reading past the end is an error
+            assert!(key.deserialize_be_u32(key.len() - U32_LEN + 1).is_err());
+            assert!(key.deserialize_be_u64(key.len() - U64_LEN + 1).is_err());
+        }
+    }
+
+    #[test]
+    fn value_serializer_roundtrip() {
+        let mut values = vec![0, 1, -1, i64::MIN, i64::MAX];
+        for _ in 0..1000 {
+            values.push(rand::random());
+        }
+
+        for value in values {
+            assert_eq!(i64::deserialize(&value.serialize()).unwrap(), value);
+            assert_eq!(
+                u64::deserialize(&(value as u64).serialize()).unwrap(),
+                value as u64
+            );
+            assert_eq!(
+                u32::deserialize(&(value as u32).serialize()).unwrap(),
+                value as u32
+            );
+        }
+
+        // Values of the wrong width are rejected
+        assert!(u32::deserialize(&[0u8; U32_LEN - 1]).is_err());
+        assert!(u64::deserialize(&[0u8; U64_LEN + 1]).is_err());
+        assert!(i64::deserialize(&[]).is_err());
+    }
+}
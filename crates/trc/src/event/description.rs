@@ -248,6 +248,10 @@ impl ImapEvent {
             ImapEvent::Subscribe => "IMAP SUBSCRIBE command",
             ImapEvent::Unsubscribe => "IMAP UNSUBSCRIBE command",
             ImapEvent::Thread => "IMAP THREAD command",
+            ImapEvent::Compress => "IMAP COMPRESS command",
+            ImapEvent::GetQuota => "IMAP GETQUOTA command",
+            ImapEvent::GetQuotaRoot => "IMAP GETQUOTAROOT command",
+            ImapEvent::UidValidityReset => "IMAP UID validity reset",
             ImapEvent::Error => "IMAP error occurred",
             ImapEvent::RawInput => "Raw IMAP input received",
             ImapEvent::RawOutput => "Raw IMAP output sent",
@@ -289,6 +293,12 @@ impl ImapEvent {
             ImapEvent::Subscribe => "Client subscribed to a mailbox",
             ImapEvent::Unsubscribe => "Client unsubscribed from a mailbox",
             ImapEvent::Thread => "Client requested message threads",
+            ImapEvent::Compress => "Client enabled stream compression",
+            ImapEvent::GetQuota => "Client requested quota usage",
+            ImapEvent::GetQuotaRoot => "Client requested mailbox quota roots",
+            ImapEvent::UidValidityReset => {
+                "The UID counter was behind the highest UID in use and the UID validity was changed"
+            }
             ImapEvent::Error => "An error occurred during an IMAP command",
             ImapEvent::RawInput => "Raw IMAP input received",
             ImapEvent::RawOutput => "Raw IMAP output sent",
@@ -1551,6 +1561,7 @@ impl StoreEvent {
             StoreEvent::UnexpectedError => "Unexpected store error",
             StoreEvent::CryptoError => "Store crypto error",
             StoreEvent::BlobMissingMarker => "Blob missing marker",
+            StoreEvent::NegativeQuota => "Negative quota usage",
             StoreEvent::SqlQuery => "SQL query executed",
             StoreEvent::LdapQuery => "LDAP query executed",
             StoreEvent::LdapBind => "LDAP bind operation",
@@ -1585,6 +1596,7 @@ impl StoreEvent {
             StoreEvent::UnexpectedError => "An unexpected store error occurred",
             StoreEvent::CryptoError => "A store crypto error occurred",
             StoreEvent::BlobMissingMarker => "The blob is missing a marker",
+            StoreEvent::NegativeQuota => "The quota usage counter of an account is negative",
             StoreEvent::SqlQuery => "An SQL query was executed",
             StoreEvent::LdapQuery => "An LDAP query was executed",
             StoreEvent::LdapBind => "An LDAP bind operation was executed",
@@ -40,11 +40,13 @@ impl EventType {
                 | StoreEvent::NotSupported
                 | StoreEvent::UnexpectedError
                 | StoreEvent::CryptoError => Level::Error,
-                StoreEvent::BlobMissingMarker => Level::Warn,
+                StoreEvent::BlobMissingMarker | StoreEvent::NegativeQuota => Level::Warn,
             },
             EventType::Jmap(_) => Level::Debug,
             EventType::Imap(event) => match event {
-                ImapEvent::ConnectionStart | ImapEvent::ConnectionEnd => Level::Info,
+                ImapEvent::ConnectionStart
+                | ImapEvent::ConnectionEnd
+                | ImapEvent::UidValidityReset => Level::Info,
                 ImapEvent::GetAcl
                 | ImapEvent::SetAcl
                 | ImapEvent::MyRights
@@ -74,6 +76,9 @@ impl EventType {
                 | ImapEvent::Subscribe
                 | ImapEvent::Unsubscribe
                 | ImapEvent::Thread
+                | ImapEvent::Compress
+                | ImapEvent::GetQuota
+                | ImapEvent::GetQuotaRoot
                 | ImapEvent::Error
                 | ImapEvent::IdleStart
                 | ImapEvent::IdleStop => Level::Debug,
@@ -291,6 +291,7 @@ impl StoreEvent {
         match self {
             Self::AssertValueFailed => "Another process has modified the value",
             Self::BlobMissingMarker => "Blob is missing marker",
+            Self::NegativeQuota => "Quota usage is negative",
             Self::FoundationdbError => "FoundationDB error",
             Self::MysqlError => "MySQL error",
             Self::PostgresqlError => "PostgreSQL error",
@@ -278,6 +278,12 @@ pub enum ImapEvent {
     Subscribe,
     Unsubscribe,
     Thread,
+    Compress,
+    GetQuota,
+    GetQuotaRoot,
+
+    // Mailbox state
+    UidValidityReset,
 
     // Errors
     Error,
@@ -839,6 +845,7 @@ pub enum StoreEvent {
 
     // Warnings
     BlobMissingMarker,
+    NegativeQuota,
 
     // Traces
     DataWrite,
@@ -858,6 +858,11 @@ impl EventType {
             EventType::Security(SecurityEvent::BruteForceBan) => 549,
             EventType::Security(SecurityEvent::LoiterBan) => 550,
             EventType::Smtp(SmtpEvent::MailFromNotAllowed) => 551,
+            EventType::Imap(ImapEvent::Compress) => 552,
+            EventType::Imap(ImapEvent::GetQuota) => 553,
+            EventType::Imap(ImapEvent::GetQuotaRoot) => 554,
+            EventType::Imap(ImapEvent::UidValidityReset) => 555,
+            EventType::Store(StoreEvent::NegativeQuota) => 556,
         }
     }
 
@@ -1455,6 +1460,11 @@ impl EventType {
             549 => Some(EventType::Security(SecurityEvent::BruteForceBan)),
             550 => Some(EventType::Security(SecurityEvent::LoiterBan)),
             551 => Some(EventType::Smtp(SmtpEvent::MailFromNotAllowed)),
+            552 => Some(EventType::Imap(ImapEvent::Compress)),
+            553 => Some(EventType::Imap(ImapEvent::GetQuota)),
+            554 => Some(EventType::Imap(ImapEvent::GetQuotaRoot)),
+            555 => Some(EventType::Imap(ImapEvent::UidValidityReset)),
+            556 => Some(EventType::Store(StoreEvent::NegativeQuota)),
             _ => None,
         }
     }
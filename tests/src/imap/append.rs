@@ -67,6 +67,115 @@ pub async fn test(imap: &mut ImapConnection, _imap_check: &mut ImapConnection, h
     wait_for_index(&handle.jmap).await;
 }
 
+pub async fn test_quota() {
+    println!("Running APPEND quota tests...");
+
+    // The test account has a quota of 1000 bytes
+    let mut imap = ImapConnection::connect(b"_y ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.send("AUTHENTICATE PLAIN {36+}\r\nAHF1b3RhQGV4YW1wbGUuY29tAHNlY3JldA==")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    // The account is the quota root of its mailboxes
+    imap.send("GETQUOTAROOT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("* QUOTAROOT \"INBOX\" \"\"")
+        .assert_contains("* QUOTA \"\" (STORAGE 0 1)");
+
+    // Messages that fit, including one that exactly fills the quota
+    for size in [400, 600] {
+        let message = format!("Subject: Quota\r\n\r\n{}", "a".repeat(size - 18));
+        assert_eq!(message.len(), size);
+        assert_append_message(&mut imap, "INBOX", &message, ResponseType::Ok).await;
+    }
+
+    // Messages over quota are rejected before the literal is sent
+    imap.send("APPEND INBOX {1}").await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_contains("[OVERQUOTA]");
+
+    // Non-synchronizing literals are rejected and their data discarded
+    imap.send("APPEND INBOX {5+}\r\nhello").await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_contains("[OVERQUOTA]");
+    imap.send("STATUS INBOX (MESSAGES SIZE)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("MESSAGES 2")
+        .assert_contains("SIZE 1000");
+
+    // Quota usage reflects the appended messages
+    imap.send("GETQUOTA \"\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("* QUOTA \"\" (STORAGE 1 1)");
+    imap.send("GETQUOTA \"Other\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_response_code("NONEXISTENT");
+    imap.send("SETQUOTA \"\" (STORAGE 2048)").await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_response_code("NOPERM");
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
+
+pub async fn test_literal_minus(handle: &IMAPTest) {
+    println!("Running LITERAL- tests...");
+
+    // Enable LITERAL- for new sessions
+    let (mut imap, _core) = handle
+        .connect_with_core(b"_l ", |core| core.imap.literal_minus = true)
+        .await;
+    imap.send("CAPABILITY").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("LITERAL-");
+    imap.send("CREATE \"Literal Minus\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    // Non-synchronizing literals within the limit are accepted
+    let message = format!("Subject: Literal\r\n\r\n{}", "a".repeat(4000));
+    imap.send(&format!(
+        "APPEND \"Literal Minus\" {{{}+}}\r\n{}",
+        message.len(),
+        message
+    ))
+    .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    // Larger literals are rejected and their data is not parsed as commands,
+    // including the literals that follow in the same command
+    let large_message = format!("Subject: Literal\r\n\r\n{}", "_l NOOP\r\n".repeat(1000));
+    imap.send(&format!(
+        "APPEND \"Literal Minus\" {{{}+}}\r\n{} {{{}+}}\r\n{}",
+        large_message.len(),
+        large_message,
+        message.len(),
+        message
+    ))
+    .await;
+    imap.assert_read(Type::Tagged, ResponseType::Bad)
+        .await
+        .assert_response_code("TOOBIG");
+    imap.send("STATUS \"Literal Minus\" (MESSAGES)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("MESSAGES 1");
+
+    // Synchronizing literals are not limited
+    assert_append_message(&mut imap, "Literal Minus", &large_message, ResponseType::Ok).await;
+    imap.send("DELETE \"Literal Minus\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
+
 pub async fn assert_append_message(
     imap: &mut ImapConnection,
     folder: &str,
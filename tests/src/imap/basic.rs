@@ -27,6 +27,14 @@ pub async fn test(imap: &mut ImapConnection, _imap_check: &mut ImapConnection) {
     imap.assert_read(Type::Tagged, ResponseType::Ok)
         .await
         .assert_contains("* ID (\"name\" \"Stalwart IMAP\" \"version\" ");
+    imap.send("ID (\"name\" \"test-client\" \"version\" \"1.0\" \"os\" NIL)")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("\"vendor\" \"Stalwart Labs Ltd.\"")
+        .assert_contains("\"support-url\" \"https://stalw.art\"");
+    imap.send("ID (\"name\")").await;
+    imap.assert_read(Type::Tagged, ResponseType::Bad).await;
 
     // Login should be disabled
     imap.send("LOGIN jdoe@example.com secret").await;
@@ -39,6 +47,49 @@ pub async fn test(imap: &mut ImapConnection, _imap_check: &mut ImapConnection) {
     imap.assert_read(Type::Tagged, ResponseType::No).await;
 }
 
+pub async fn test_compress() {
+    println!("Running COMPRESS tests...");
+
+    let mut imap = ImapConnection::connect(b"_z ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+
+    // COMPRESS requires authentication
+    imap.send("COMPRESS DEFLATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+
+    imap.send("AUTHENTICATE PLAIN {32+}\r\nAGpkb2VAZXhhbXBsZS5jb20Ac2VjcmV0")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("CAPABILITY").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("COMPRESS=DEFLATE");
+
+    // Unsupported algorithms are rejected
+    imap.send("COMPRESS GZIP").await;
+    imap.assert_read(Type::Tagged, ResponseType::Bad).await;
+
+    // Activate compression and round-trip commands through the deflated stream
+    imap.send("COMPRESS DEFLATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    let mut imap = imap.into_compressed();
+    imap.send("NOOP").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LIST \"\" \"*\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("INBOX");
+
+    // A second COMPRESS is not allowed
+    imap.send("COMPRESS DEFLATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_response_code("COMPRESSIONACTIVE");
+
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
+
 #[test]
 fn decode_challenge() {
     assert!(
@@ -4,10 +4,14 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use ::store::write::BatchBuilder;
+use common::config::imap::ImapConfig;
 use imap::op::list::matches_pattern;
 use imap_proto::ResponseType;
+use jmap_proto::types::{collection::Collection, id::Id, property::Property};
+use utils::config::Config;
 
-use super::{AssertResult, ImapConnection, Type};
+use super::{append::assert_append_message, AssertResult, IMAPTest, ImapConnection, Type};
 
 pub async fn test(mut imap: &mut ImapConnection, mut imap_check: &mut ImapConnection) {
     println!("Running mailbox tests...");
@@ -57,14 +61,58 @@ pub async fn test(mut imap: &mut ImapConnection, mut imap_check: &mut ImapConnec
             );
     }
 
+    // Empty path segments and bare separators are not allowed
+    for name in ["Fruit//Banana", "/", "//", "Fruit/ /Banana"] {
+        imap.send(&format!("CREATE \"{name}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::No).await;
+    }
+
+    // A single trailing separator is removed before checking for duplicates
+    imap.send("CREATE \"Fruit/\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+
+    // INBOX and existing mailboxes cannot be created
+    for name in ["INBOX", "inbox", "Inbox", "Deleted Items", "Tofu"] {
+        imap.send(&format!("CREATE \"{name}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::No)
+            .await
+            .assert_contains("[ALREADYEXISTS]");
+    }
+
+    // Mailbox names are case sensitive
+    imap.send("CREATE \"tofu\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("DELETE \"tofu\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
     // Special use folders that already exist should not be allowed
     imap.send("CREATE \"Second trash\" (USE (\\Trash))").await;
     imap.assert_read(Type::Tagged, ResponseType::No).await;
 
+    // IMAP4rev1 clients use modified UTF-7 for non-ASCII names
+    imap.send("CREATE \"&ZeVnLIqe-\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LIST \"\" \"&ZeVnLIqe-\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_folders([("&ZeVnLIqe-", [""])], true);
+    for name in ["日本語", "&ZeVnLIqe"] {
+        imap.send(&format!("CREATE \"{name}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::Bad).await;
+    }
+
     // Enable IMAP4rev2
     imap.send("ENABLE IMAP4rev2").await;
     imap.assert_read(Type::Tagged, ResponseType::Ok).await;
 
+    // IMAP4rev2 clients see the same name in UTF-8
+    imap.send("LIST \"\" \"日本語\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_folders([("日本語", [""])], true);
+    imap.send("DELETE \"日本語\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
     // Create missing parent folders
     imap.send("CREATE \"/Vegetable/Broccoli\" (USE (\\Important))")
         .await;
@@ -145,7 +193,15 @@ pub async fn test(mut imap: &mut ImapConnection, mut imap_check: &mut ImapConnec
     imap.send("DELETE \"Vegetable\"").await;
     imap.assert_read(Type::Tagged, ResponseType::Ok).await;
     imap.send("DELETE \"Vehicles\"").await;
-    imap.assert_read(Type::Tagged, ResponseType::No).await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_contains("[HASCHILDREN]");
+
+    // Children of a mailbox that could not be deleted are still selectable
+    other_conn
+        .send("SELECT \"Vehicles/Electric/4 doors/Red\"")
+        .await;
+    other_conn.assert_read(Type::Tagged, ResponseType::Ok).await;
     for imap in [&mut imap, &mut imap_check] {
         imap.send("LIST \"\" \"*\" RETURN (CHILDREN SPECIAL-USE)")
             .await;
@@ -306,6 +362,109 @@ pub async fn test(mut imap: &mut ImapConnection, mut imap_check: &mut ImapConnec
     imap.assert_read(Type::Tagged, ResponseType::Ok).await;
 }
 
+pub async fn test_case_insensitive(handle: &IMAPTest) {
+    println!("Running case-insensitive mailbox tests...");
+
+    // Enable case-insensitive mailbox names for new sessions
+    let (mut imap, _core) = handle
+        .connect_with_core(b"_w ", |core| core.imap.folders_case_insensitive = true)
+        .await;
+
+    // Names that only differ in case collide
+    imap.send("CREATE \"Foo\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    for name in ["foo", "FOO", "inbox"] {
+        imap.send(&format!("CREATE \"{name}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::No)
+            .await
+            .assert_contains("[ALREADYEXISTS]");
+    }
+
+    // Parents are matched regardless of case and keep their original casing
+    imap.send("CREATE \"foo/Bar\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LIST \"\" \"Foo*\" RETURN (CHILDREN)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_folders(
+            [("Foo", ["HasChildren"]), ("Foo/Bar", ["HasNoChildren"])],
+            true,
+        );
+
+    // Clean up
+    for name in ["Foo/Bar", "Foo"] {
+        imap.send(&format!("DELETE \"{name}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    }
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
+
+pub async fn test_role_inference(handle: &IMAPTest) {
+    println!("Running special use inference tests...");
+
+    // Enable role inference for new sessions, replacing the German drafts folder names
+    let (mut imap, _core) = handle
+        .connect_with_core(b"_v ", |core| {
+            core.imap.folder_roles = ImapConfig::parse(
+                &mut Config::new(concat!(
+                    "[imap.folders.infer-role]\n",
+                    "enable = true\n",
+                    "[imap.folders.infer-role.de]\n",
+                    "drafts = [\"Vorlagen\"]\n",
+                ))
+                .unwrap(),
+            )
+            .folder_roles
+        })
+        .await;
+
+    for (create, name, expected_role, unexpected_role) in [
+        // Built-in names that were replaced are no longer inferred
+        ("CREATE \"Entw&APw-rfe\"", "Entw&APw-rfe", None, "\\Drafts"),
+        // Names are matched case-insensitively
+        ("CREATE \"gesendet\"", "gesendet", Some("\\Sent"), ""),
+        ("CREATE \"Vorlagen\"", "Vorlagen", Some("\\Drafts"), ""),
+        // An explicit USE clause wins over inference
+        (
+            "CREATE \"Archiv\" (USE (\\Junk))",
+            "Archiv",
+            Some("\\Junk"),
+            "\\Archive",
+        ),
+        // Roles already in use are not inferred
+        ("CREATE \"Sent\"", "Sent", None, "\\Sent"),
+        ("CREATE \"Archives\"", "Archives", Some("\\Archive"), ""),
+    ] {
+        imap.send(create).await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+        imap.send(&format!("LIST \"\" \"{name}\" RETURN (SPECIAL-USE)"))
+            .await;
+        let result = imap
+            .assert_read(Type::Tagged, ResponseType::Ok)
+            .await
+            .assert_folders([(name, [expected_role.unwrap_or_default()])], true);
+        if !unexpected_role.is_empty() {
+            result.assert_count(unexpected_role, 0);
+        }
+    }
+
+    // Clean up
+    for name in [
+        "Entw&APw-rfe",
+        "gesendet",
+        "Vorlagen",
+        "Archiv",
+        "Sent",
+        "Archives",
+    ] {
+        imap.send(&format!("DELETE \"{name}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    }
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
+
 #[test]
 fn mailbox_matches_pattern() {
     let mailboxes = [
@@ -377,3 +536,143 @@ fn mailbox_matches_pattern() {
         assert_eq!(matched_mailboxes, expected_match, "for pattern {}", pattern);
     }
 }
+
+pub async fn test_rename_inbox() {
+    println!("Running INBOX rename tests...");
+
+    let mut imap = ImapConnection::connect(b"_x ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.send("AUTHENTICATE PLAIN {36+}\r\nAGZvb2JhckBleGFtcGxlLmNvbQBzZWNyZXQ=")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    for message in [
+        "Subject: Renamed INBOX 1\r\n\r\nTest 1\r\n",
+        "Subject: Renamed INBOX 2\r\n\r\nTest 2\r\n",
+    ] {
+        imap.send(&format!(
+            "APPEND INBOX {{{}+}}\r\n{}",
+            message.len(),
+            message
+        ))
+        .await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    }
+
+    // Renaming INBOX moves its messages to the new mailbox
+    imap.send("RENAME INBOX \"Old Inbox\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LIST \"\" % RETURN (STATUS (MESSAGES))").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("\"INBOX\" (MESSAGES 0)")
+        .assert_contains("\"Old Inbox\" (MESSAGES 2)");
+    imap.send("SELECT \"Old Inbox\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("FETCH 1:* (BODY.PEEK[HEADER.FIELDS (SUBJECT)])")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("Renamed INBOX 1")
+        .assert_contains("Renamed INBOX 2");
+    imap.send("UNSELECT").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    // Renaming INBOX to an existing mailbox fails
+    imap.send("RENAME INBOX \"Old Inbox\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_contains("[ALREADYEXISTS]");
+
+    // Clean up
+    imap.send("DELETE \"Old Inbox\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
+
+pub async fn test_uid_validity(handle: &IMAPTest) {
+    println!("Running UID validity recovery tests...");
+
+    let mut imap = ImapConnection::connect(b"_u ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.send("AUTHENTICATE PLAIN {32+}\r\nAGpkb2VAZXhhbXBsZS5jb20Ac2VjcmV0")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    imap.send("CREATE \"Restored\"").await;
+    let mailbox_id = imap
+        .assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .into_response_code();
+    let mailbox_id = Id::from_bytes(
+        mailbox_id
+            .trim_start_matches("MAILBOXID (")
+            .trim_end_matches(')')
+            .as_bytes(),
+    )
+    .unwrap();
+    for message in [
+        "Subject: UID 1\r\n\r\nTest\r\n",
+        "Subject: UID 2\r\n\r\nTest\r\n",
+    ] {
+        assert_append_message(&mut imap, "Restored", message, ResponseType::Ok).await;
+    }
+    imap.send("SELECT \"Restored\"").await;
+    let uid_validity = imap
+        .assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .into_uid_validity()
+        .parse::<u32>()
+        .unwrap();
+
+    // Lose the UID counter, as a partial restore would
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(mailbox_id.prefix_id())
+        .with_collection(Collection::Mailbox)
+        .update_document(mailbox_id.document_id())
+        .clear(Property::EmailIds);
+    handle
+        .jmap
+        .core
+        .storage
+        .data
+        .write(batch.build())
+        .await
+        .unwrap();
+
+    // SELECT checks the UID counter, even when the mailbox state is cached
+    imap.send("SELECT \"Restored\"").await;
+    let new_uid_validity = imap
+        .assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .into_uid_validity()
+        .parse::<u32>()
+        .unwrap();
+    assert!(
+        new_uid_validity > uid_validity,
+        "UID validity {new_uid_validity} is not higher than {uid_validity}"
+    );
+
+    // New messages no longer reuse UIDs
+    assert_eq!(
+        assert_append_message(
+            &mut imap,
+            "Restored",
+            "Subject: UID 3\r\n\r\nTest\r\n",
+            ResponseType::Ok
+        )
+        .await
+        .into_append_uid(),
+        "3"
+    );
+
+    // Clean up
+    imap.send("UNSELECT").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("DELETE \"Restored\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
@@ -31,19 +31,22 @@ use common::{
         server::{ServerProtocol, Servers},
         telemetry::Telemetry,
     },
-    Core, Ipc, IPC_CHANNEL_BUFFER,
+    Core, Ipc, SharedCore, IPC_CHANNEL_BUFFER,
 };
 
 use ::store::Stores;
 use ahash::AHashSet;
 use directory::backend::internal::manage::ManageDirectory;
-use imap::core::{ImapSessionManager, Inner, IMAP};
+use imap::core::{compress::DeflateStream, ImapSessionManager, Inner, IMAP};
 use imap_proto::ResponseType;
 use jmap::{api::JmapSessionManager, JMAP};
 use pop3::Pop3SessionManager;
 use smtp::core::{SmtpSessionManager, SMTP};
 use tokio::{
-    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines, ReadHalf, WriteHalf},
+    io::{
+        AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines, ReadHalf,
+        WriteHalf,
+    },
     net::TcpStream,
     sync::{mpsc, watch},
 };
@@ -412,6 +415,10 @@ async fn init_imap_tests(store_id: &str, delete_if_exists: bool) -> IMAPTest {
     lookup
         .create_test_user_with_email("popper@example.com", "secret", "Karl Popper")
         .await;
+    lookup
+        .create_test_user_with_email("quota@example.com", "secret", "Quota Test")
+        .await;
+    lookup.set_test_quota("quota@example.com", 1000).await;
     lookup
         .create_test_group_with_email("support@example.com", "Support Group")
         .await;
@@ -434,6 +441,45 @@ async fn init_imap_tests(store_id: &str, delete_if_exists: bool) -> IMAPTest {
     }
 }
 
+impl IMAPTest {
+    // Applies the changes to the shared core and opens a session authenticated as
+    // jdoe@example.com, the original core is restored when the guard is dropped
+    pub async fn connect_with_core(
+        &self,
+        tag: &'static [u8],
+        update: impl FnOnce(&mut Core),
+    ) -> (ImapConnection, CoreGuard) {
+        let shared_core = self.jmap.shared_core.clone();
+        let original_core = shared_core.load_full();
+        let mut core = original_core.as_ref().clone();
+        update(&mut core);
+        shared_core.store(core.into());
+        let guard = CoreGuard {
+            shared_core,
+            original_core,
+        };
+
+        let mut imap = ImapConnection::connect(tag).await;
+        imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+        imap.send("AUTHENTICATE PLAIN {32+}\r\nAGpkb2VAZXhhbXBsZS5jb20Ac2VjcmV0")
+            .await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+        (imap, guard)
+    }
+}
+
+pub struct CoreGuard {
+    shared_core: SharedCore,
+    original_core: Arc<Core>,
+}
+
+impl Drop for CoreGuard {
+    fn drop(&mut self) {
+        self.shared_core.store(self.original_core.clone());
+    }
+}
+
 #[tokio::test]
 pub async fn imap_tests() {
     // Prepare settings
@@ -470,7 +516,13 @@ pub async fn imap_tests() {
     }
 
     mailbox::test(&mut imap, &mut imap_check).await;
+    mailbox::test_case_insensitive(&handle).await;
+    mailbox::test_role_inference(&handle).await;
+    mailbox::test_rename_inbox().await;
+    mailbox::test_uid_validity(&handle).await;
     append::test(&mut imap, &mut imap_check, &handle).await;
+    append::test_quota().await;
+    append::test_literal_minus(&handle).await;
     search::test(&mut imap, &mut imap_check).await;
     fetch::test(&mut imap, &mut imap_check).await;
     store::test(&mut imap, &mut imap_check, &handle).await;
@@ -479,6 +531,7 @@ pub async fn imap_tests() {
     idle::test(&mut imap, &mut imap_check).await;
     condstore::test(&mut imap, &mut imap_check).await;
     acl::test(&mut imap, &mut imap_check).await;
+    basic::test_compress().await;
 
     // Logout
     for imap in [&mut imap, &mut imap_check] {
@@ -509,10 +562,10 @@ pub async fn imap_tests() {
     }
 }
 
-pub struct ImapConnection {
+pub struct ImapConnection<T = TcpStream> {
     tag: &'static [u8],
-    reader: Lines<BufReader<ReadHalf<TcpStream>>>,
-    writer: WriteHalf<TcpStream>,
+    reader: Lines<BufReader<ReadHalf<T>>>,
+    writer: WriteHalf<T>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -534,6 +587,20 @@ impl ImapConnection {
         }
     }
 
+    pub fn into_compressed(self) -> ImapConnection<DeflateStream<TcpStream>> {
+        let (reader, writer) = tokio::io::split(DeflateStream::new(
+            self.reader.into_inner().into_inner().unsplit(self.writer),
+            Vec::new(),
+        ));
+        ImapConnection {
+            tag: self.tag,
+            reader: BufReader::new(reader).lines(),
+            writer,
+        }
+    }
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> ImapConnection<T> {
     pub async fn assert_read(&mut self, t: Type, rt: ResponseType) -> Vec<String> {
         let lines = self.read(t).await;
         let mut buf = Vec::with_capacity(10);
@@ -601,17 +668,20 @@ impl ImapConnection {
         self.writer.write_all(self.tag).await.unwrap();
         self.writer.write_all(text.as_bytes()).await.unwrap();
         self.writer.write_all(b"\r\n").await.unwrap();
+        self.writer.flush().await.unwrap();
     }
 
     pub async fn send_untagged(&mut self, text: &str) {
         //let c = println!("-> {:?}", text);
         self.writer.write_all(text.as_bytes()).await.unwrap();
         self.writer.write_all(b"\r\n").await.unwrap();
+        self.writer.flush().await.unwrap();
     }
 
     pub async fn send_raw(&mut self, text: &str) {
         //let c = println!("-> {:?}", text);
         self.writer.write_all(text.as_bytes()).await.unwrap();
+        self.writer.flush().await.unwrap();
     }
 }
 
@@ -594,6 +594,39 @@ pub async fn test(params: &mut JMAPTest) {
         ["inbox", "sent", "spam"]
     );
 
+    // Import and export subscriptions
+    let mut expected = server.export_subscriptions(0).await.unwrap();
+    let import = [
+        "Sent",
+        "Spam/Work Spam",
+        "Spam/Work Spam/Friendly Spam",
+        "Spam/Does not exist",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+    assert_eq!(
+        server
+            .import_subscriptions(0, import.clone())
+            .await
+            .unwrap(),
+        vec!["Spam/Does not exist".to_string()]
+    );
+    for name in &import[..3] {
+        if !expected.contains(name) {
+            expected.push(name.clone());
+        }
+    }
+    expected.sort_unstable();
+    assert_eq!(server.export_subscriptions(0).await.unwrap(), expected);
+
+    // Importing again does not change the subscriptions
+    assert_eq!(
+        server.import_subscriptions(0, import).await.unwrap(),
+        vec!["Spam/Does not exist".to_string()]
+    );
+    assert_eq!(server.export_subscriptions(0).await.unwrap(), expected);
+
     destroy_all_mailboxes(params).await;
     params.client.set_default_account_id(Id::from(1u64));
     assert_is_empty(server).await;
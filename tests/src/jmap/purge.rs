@@ -8,18 +8,18 @@ use ahash::AHashSet;
 use directory::backend::internal::manage::ManageDirectory;
 use imap_proto::ResponseType;
 use jmap::{
-    mailbox::{INBOX_ID, JUNK_ID, TRASH_ID},
+    mailbox::{verify::MailboxConsistency, UidMailbox, INBOX_ID, JUNK_ID, TRASH_ID},
     JMAP,
 };
 use jmap_proto::types::{collection::Collection, id::Id, property::Property};
 use store::{
-    write::{key::DeserializeBigEndian, TagValue},
-    IterateParams, LogKey, U32_LEN, U64_LEN,
+    write::{key::DeserializeBigEndian, BatchBuilder, TagValue},
+    IterateParams, LogKey, Serialize, U32_LEN, U64_LEN,
 };
 
 use crate::imap::{AssertResult, ImapConnection, Type};
 
-use super::JMAPTest;
+use super::{JMAPTest, ManagementApi};
 
 pub async fn test(params: &mut JMAPTest) {
     println!("Running purge tests...");
@@ -188,6 +188,71 @@ pub async fn test(params: &mut JMAPTest) {
             change
         );
     }
+
+    // Mailbox bitmaps and MailboxIds values should agree
+    assert_eq!(
+        server.verify_mailbox(account_id, INBOX_ID).await.unwrap(),
+        MailboxConsistency::default()
+    );
+
+    // Remove a MailboxIds value without clearing its bitmap entry
+    let document_id = server
+        .get_tag(
+            account_id,
+            Collection::Email,
+            Property::MailboxIds,
+            TagValue::Id(INBOX_ID),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .min()
+        .unwrap();
+    let mailboxes = server
+        .get_property::<Vec<UidMailbox>>(
+            account_id,
+            Collection::Email,
+            document_id,
+            Property::MailboxIds,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(account_id)
+        .with_collection(Collection::Email)
+        .update_document(document_id)
+        .clear(Property::MailboxIds);
+    server.core.storage.data.write(batch.build()).await.unwrap();
+    let result = server.verify_mailbox(account_id, INBOX_ID).await.unwrap();
+    assert_eq!(
+        result.missing_values.iter().collect::<Vec<_>>(),
+        vec![document_id]
+    );
+    assert!(result.missing_bitmap.is_empty());
+
+    // The orphan is also reported by the management API
+    let data = ManagementApi::new(8899, "admin", "secret")
+        .get::<serde_json::Value>(&format!("/api/store/verify/jdoe@example.com/{inbox_id}"))
+        .await
+        .unwrap()
+        .unwrap_data();
+    assert_eq!(data["missingValues"], serde_json::json!([document_id]));
+    assert_eq!(data["missingBitmap"], serde_json::json!([]));
+
+    // Restore the value
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(account_id)
+        .with_collection(Collection::Email)
+        .update_document(document_id)
+        .set(Property::MailboxIds, mailboxes.serialize());
+    server.core.storage.data.write(batch.build()).await.unwrap();
+    assert_eq!(
+        server.verify_mailbox(account_id, INBOX_ID).await.unwrap(),
+        MailboxConsistency::default()
+    );
 }
 
 async fn get_changes(server: &JMAP) -> AHashSet<(u64, u8)> {
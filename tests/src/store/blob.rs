@@ -476,4 +476,30 @@ async fn test_store(store: BlobStore) {
         .await
         .unwrap()
         .is_none());
+
+    // Test binary blob spanning multiple chunks, including NUL bytes
+    let mut data = Vec::with_capacity(1024 * 1024);
+    while data.len() < 1024 * 1024 {
+        data.extend(0..=u8::MAX);
+        data.extend_from_slice(b"\0\0\r\n\0");
+    }
+    let hash = BlobHash::from(&data);
+    store.put_blob(hash.as_slice(), &data).await.unwrap();
+    assert_eq!(
+        store
+            .get_blob(hash.as_slice(), 0..usize::MAX)
+            .await
+            .unwrap()
+            .unwrap(),
+        data
+    );
+    assert_eq!(
+        store
+            .get_blob(hash.as_slice(), 99990..100010)
+            .await
+            .unwrap()
+            .unwrap(),
+        &data[99990..100010]
+    );
+    assert!(store.delete_blob(hash.as_slice()).await.unwrap());
 }
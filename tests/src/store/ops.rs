@@ -11,7 +11,7 @@ use store::{
     write::{
         BatchBuilder, BitmapClass, DirectoryClass, MaybeDynamicId, TagValue, ValueClass, F_CLEAR,
     },
-    BitmapKey, Store, ValueKey,
+    BitmapKey, IterateParams, Key, Store, ValueKey,
 };
 
 // FDB max value
@@ -98,6 +98,163 @@ pub async fn test(db: Store) {
         db.write(batch.build_batch()).await.unwrap();
     }
 
+    // Make sure first-only iteration returns the value rather than the key
+    println!("Running first-only iteration tests...");
+    set_config(&db, [("first-a", "value-a"), ("first-b", "value-b")]).await;
+    for (ascending, expected) in [
+        (true, ("first-a", "value-a")),
+        (false, ("first-b", "value-b")),
+    ] {
+        assert_eq!(
+            iterate_config(
+                &db,
+                config_range(b"first-", b"first-\xFF")
+                    .set_ascending(ascending)
+                    .only_first()
+            )
+            .await,
+            vec![(
+                expected.0.as_bytes().to_vec(),
+                expected.1.as_bytes().to_vec()
+            )],
+            "failed for ascending={ascending}"
+        );
+    }
+    clear_config(&db, ["first-a", "first-b"]).await;
+
+    // Test iteration row limits
+    println!("Running iteration limit tests...");
+    let keys = (0..5).map(|n| format!("limit-{n}")).collect::<Vec<_>>();
+    set_config(&db, keys.iter().map(|key| (key, key))).await;
+    for limit in [0, 1, 3, 5, 10] {
+        for ascending in [true, false] {
+            let mut expected = keys
+                .iter()
+                .map(|key| (key.as_bytes().to_vec(), key.as_bytes().to_vec()))
+                .collect::<Vec<_>>();
+            if !ascending {
+                expected.reverse();
+            }
+            expected.truncate(limit);
+            assert_eq!(
+                iterate_config(
+                    &db,
+                    config_range(b"limit-", b"limit-\xFF")
+                        .set_ascending(ascending)
+                        .set_limit(limit)
+                )
+                .await,
+                expected,
+                "failed for limit={limit} ascending={ascending}"
+            );
+        }
+    }
+    clear_config(&db, &keys).await;
+
+    // Empty and inverted ranges return no results instead of failing
+    println!("Running empty range iteration tests...");
+    set_config(&db, [("range-a", "range-a"), ("range-b", "range-b")]).await;
+    for (begin, end, expected) in [
+        ("range-a", "range-a", vec!["range-a"]),
+        ("range-b", "range-a", vec![]),
+        ("range-c", "range-", vec![]),
+    ] {
+        for ascending in [true, false] {
+            assert_eq!(
+                iterate_config(
+                    &db,
+                    config_range(begin.as_bytes(), end.as_bytes()).set_ascending(ascending)
+                )
+                .await,
+                expected
+                    .iter()
+                    .map(|key| (key.as_bytes().to_vec(), key.as_bytes().to_vec()))
+                    .collect::<Vec<_>>(),
+                "failed for begin={begin:?} end={end:?} ascending={ascending}"
+            );
+        }
+    }
+    clear_config(&db, ["range-a", "range-b"]).await;
+
+    // Test compare and swap
+    println!("Running compare and swap tests...");
+    let large_value = "x".repeat(MAX_VALUE_SIZE * 2);
+    let large_value = large_value.as_str();
+    for (step, (expected, new, swapped, result)) in [
+        // Insert if absent
+        (None, Some("cas-1"), true, Some("cas-1")),
+        (None, Some("cas-2"), false, Some("cas-1")),
+        // Mismatch does not write
+        (Some("cas-2"), Some("cas-3"), false, Some("cas-1")),
+        // Successful swap, including chunked values
+        (Some("cas-1"), Some(large_value), true, Some(large_value)),
+        (Some(large_value), Some("cas-2"), true, Some("cas-2")),
+        // Delete
+        (Some("cas-1"), None, false, Some("cas-2")),
+        (Some("cas-2"), None, true, None),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        assert_eq!(
+            db.compare_and_swap_value(
+                0,
+                0,
+                0,
+                ValueClass::Config(b"cas".to_vec()),
+                expected.map(str::as_bytes),
+                new.map(str::as_bytes),
+            )
+            .await
+            .unwrap(),
+            swapped,
+            "failed for step {step}"
+        );
+        assert_eq!(
+            get_config(&db, "cas").await.as_deref(),
+            result,
+            "failed for step {step}"
+        );
+    }
+
+    // Testing prefix iteration
+    println!("Running prefix iteration tests...");
+    let keys: [&[u8]; 8] = [
+        b"prefix",
+        b"prefix-a",
+        b"prefix-b",
+        b"prefix.",
+        b"prefix\xFF",
+        b"prefix\xFF\xFFa",
+        b"prefiy",
+        b"\xFF\xFFz",
+    ];
+    set_config(&db, keys.iter().map(|key| (key, key))).await;
+    for (prefix, end) in [
+        (&b"prefix-"[..], &b"prefix-\xFF"[..]),
+        (&b"prefix\xFF"[..], &b"prefix\xFF\xFF\xFF"[..]),
+        (&b"prefix"[..], &b"prefix\xFF\xFF\xFF"[..]),
+        (&b"\xFF"[..], &b"\xFF\xFF\xFF\xFF"[..]),
+    ] {
+        for ascending in [true, false] {
+            let results = iterate_config(
+                &db,
+                IterateParams::prefix(config_key(prefix)).set_ascending(ascending),
+            )
+            .await;
+            let expected =
+                iterate_config(&db, config_range(prefix, end).set_ascending(ascending)).await;
+
+            assert!(!expected.is_empty());
+            assert!(expected.iter().all(|(key, _)| key.starts_with(prefix)));
+            assert_eq!(
+                results, expected,
+                "failed for prefix={prefix:?} ascending={ascending}"
+            );
+        }
+    }
+    clear_config(&db, keys).await;
+
     // Testing ID assignment
     println!("Running dynamic ID assignment tests...");
     let mut builder = BatchBuilder::new();
@@ -236,6 +393,7 @@ pub async fn test(db: Store) {
         vec![b'A'; 0],
         vec![b'A'; 1],
         vec![b'A'; 100],
+        vec![b'A'; MAX_VALUE_SIZE - 1],
         vec![b'A'; MAX_VALUE_SIZE],
         vec![b'B'; MAX_VALUE_SIZE + 1],
         vec![b'C'; MAX_VALUE_SIZE]
@@ -343,4 +501,255 @@ pub async fn test(db: Store) {
         // Make sure everything is deleted
         db.assert_is_empty(db.clone().into()).await;
     }
+
+    println!("Running chunk neighbor tests...");
+    let value = vec![b'A'; MAX_VALUE_SIZE * 3];
+    let neighbors: [(&[u8], &str); 5] = [
+        // The value key followed by a chunk number
+        (b"chunked\x00", "neighbor1"),
+        (b"chunked\x01", "neighbor2"),
+        (b"chunked\x03", "neighbor3"),
+        // Same length as the value key followed by a chunk number
+        (b"chunkedz", "neighbor4"),
+        // Sorts between the value key and its neighbors
+        (b"chunked\x01next", "neighbor5"),
+    ];
+    set_config(
+        &db,
+        neighbors
+            .iter()
+            .map(|(key, value)| (*key, value.as_bytes()))
+            .chain([(&b"chunked"[..], value.as_slice())]),
+    )
+    .await;
+    assert_eq!(
+        get_config(&db, "chunked").await,
+        Some(String::from_utf8(value).unwrap())
+    );
+
+    // Chunks are stored apart from the value key under a fixed format
+    #[cfg(feature = "foundationdb")]
+    if matches!(db, Store::FoundationDb(_)) {
+        assert_eq!(
+            chunk_keys(&db).await,
+            (0..3)
+                .map(|chunk_id| chunk_key(b"schunked", chunk_id))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    // Shrinking and clearing the value must not remove its neighbors, nor
+    // reassemble chunks of the previous value
+    for value in [
+        vec![b'B'; MAX_VALUE_SIZE * 2 - 1],
+        vec![b'C'; MAX_VALUE_SIZE],
+        vec![b'D'; 10],
+    ] {
+        set_config(&db, [("chunked".as_bytes(), value.as_slice())]).await;
+        assert_eq!(
+            get_config(&db, "chunked").await,
+            Some(String::from_utf8(value).unwrap())
+        );
+    }
+    clear_config(&db, ["chunked"]).await;
+    assert_eq!(get_config(&db, "chunked").await, None);
+    for (key, value) in neighbors {
+        assert_eq!(get_config(&db, key).await.as_deref(), Some(value));
+    }
+    clear_config(&db, neighbors.map(|(key, _)| key)).await;
+    db.assert_is_empty(db.clone().into()).await;
+
+    #[cfg(feature = "foundationdb")]
+    if matches!(db, Store::FoundationDb(_)) {
+        println!("Running missing chunk tests...");
+        assert_eq!(chunk_keys(&db).await, Vec::<Vec<u8>>::new());
+        set_config(
+            &db,
+            [("damaged".as_bytes(), vec![b'A'; MAX_VALUE_SIZE * 3])],
+        )
+        .await;
+
+        // Remove the middle chunk
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(0)
+            .with_collection(0)
+            .update_document(0)
+            .clear(ValueClass::Any(store::write::AnyClass {
+                subspace: store::SUBSPACE_CHUNKS,
+                key: chunk_key(b"sdamaged", 1),
+            }));
+        db.write(batch.build_batch()).await.unwrap();
+        let err = db
+            .get_value::<String>(config_key("damaged"))
+            .await
+            .unwrap_err();
+        assert!(
+            err.matches(trc::EventType::Store(trc::StoreEvent::DataCorruption)),
+            "{err:?}"
+        );
+        clear_config(&db, ["damaged"]).await;
+        assert_eq!(chunk_keys(&db).await, Vec::<Vec<u8>>::new());
+        db.assert_is_empty(db.clone().into()).await;
+
+        println!("Running legacy chunk tests...");
+        let legacy_value = [
+            "A".repeat(MAX_VALUE_SIZE - 1),
+            "\x01".to_string(),
+            "B".repeat(10),
+        ]
+        .concat();
+        write_legacy_value(&db, b"legacy", &legacy_value).await;
+        assert_eq!(get_config(&db, "legacy").await, Some(legacy_value.clone()));
+        clear_config(&db, [&b"legacy"[..], &b"legacy\x00"[..]]).await;
+        db.assert_is_empty(db.clone().into()).await;
+
+        println!("Running chunk migration tests...");
+        let value = "C".repeat(MAX_VALUE_SIZE * 2);
+        write_legacy_value(&db, b"migrate", &legacy_value).await;
+        set_config(&db, [("migrate-new", &value)]).await;
+
+        // Only the value in the previous layout is rewritten
+        assert_eq!(db.migrate_chunks().await.unwrap(), 1);
+        assert_eq!(
+            chunk_keys(&db).await,
+            [
+                chunk_key(b"smigrate", 0),
+                chunk_key(b"smigrate-new", 0),
+                chunk_key(b"smigrate-new", 1),
+            ]
+        );
+        for (key, value) in [
+            (&b"migrate"[..], Some(legacy_value)),
+            (&b"migrate-new"[..], Some(value)),
+            (&b"migrate\x00"[..], None),
+        ] {
+            assert_eq!(get_config(&db, key).await, value, "failed for key {key:?}");
+        }
+        assert_eq!(db.migrate_chunks().await.unwrap(), 0);
+
+        clear_config(&db, ["migrate", "migrate-new"]).await;
+        assert_eq!(chunk_keys(&db).await, Vec::<Vec<u8>>::new());
+        db.assert_is_empty(db.clone().into()).await;
+    }
+}
+
+fn config_key(key: impl AsRef<[u8]>) -> ValueKey<ValueClass<u32>> {
+    ValueKey::from(ValueClass::Config(key.as_ref().to_vec()))
+}
+
+fn config_range(begin: &[u8], end: &[u8]) -> IterateParams<ValueKey<ValueClass<u32>>> {
+    IterateParams::new(config_key(begin), config_key(end))
+}
+
+async fn get_config(db: &Store, key: impl AsRef<[u8]>) -> Option<String> {
+    db.get_value::<String>(config_key(key)).await.unwrap()
+}
+
+async fn set_config(
+    db: &Store,
+    values: impl IntoIterator<Item = (impl AsRef<[u8]>, impl AsRef<[u8]>)>,
+) {
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(0)
+        .with_collection(0)
+        .update_document(0);
+    for (key, value) in values {
+        batch.set(
+            ValueClass::Config(key.as_ref().to_vec()),
+            value.as_ref().to_vec(),
+        );
+    }
+    db.write(batch.build_batch()).await.unwrap();
+}
+
+async fn clear_config(db: &Store, keys: impl IntoIterator<Item = impl AsRef<[u8]>>) {
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(0)
+        .with_collection(0)
+        .update_document(0);
+    for key in keys {
+        batch.clear(ValueClass::Config(key.as_ref().to_vec()));
+    }
+    db.write(batch.build_batch()).await.unwrap();
+}
+
+async fn iterate_config(db: &Store, params: IterateParams<impl Key>) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut results = Vec::new();
+    db.iterate(params, |key, value| {
+        results.push((key.to_vec(), value.to_vec()));
+        Ok(true)
+    })
+    .await
+    .unwrap();
+    results
+}
+
+// Value key escaped with 0x00 0xFF, terminated by 0x00 and followed by the chunk number
+#[cfg(feature = "foundationdb")]
+fn chunk_key(key: &[u8], chunk_id: u8) -> Vec<u8> {
+    let mut chunk_key = Vec::new();
+    for &byte in key {
+        chunk_key.push(byte);
+        if byte == 0 {
+            chunk_key.push(u8::MAX);
+        }
+    }
+    chunk_key.push(0);
+    chunk_key.push(chunk_id);
+    chunk_key
+}
+
+// Stores a value in the layout used before chunks were moved to SUBSPACE_CHUNKS, where
+// the value key holds the first MAX_VALUE_SIZE bytes and the rest follows under the
+// value key and chunk number. The byte at MAX_VALUE_SIZE - 1 of the value must be 1,
+// the number of chunks the current writer stores for the head.
+#[cfg(feature = "foundationdb")]
+async fn write_legacy_value(db: &Store, key: &[u8], value: &str) {
+    let (head, tail) = value.split_at(MAX_VALUE_SIZE);
+    assert_eq!(head.as_bytes().last(), Some(&1));
+    set_config(db, [(key, head)]).await;
+
+    let mut value_key = vec![store::SUBSPACE_SETTINGS];
+    value_key.extend_from_slice(key);
+    let mut legacy_key = key.to_vec();
+    legacy_key.push(0);
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(0)
+        .with_collection(0)
+        .update_document(0)
+        .clear(ValueClass::Any(store::write::AnyClass {
+            subspace: store::SUBSPACE_CHUNKS,
+            key: chunk_key(&value_key, 0),
+        }))
+        .set(ValueClass::Config(legacy_key), tail.as_bytes());
+    db.write(batch.build_batch()).await.unwrap();
+}
+
+#[cfg(feature = "foundationdb")]
+async fn chunk_keys(db: &Store) -> Vec<Vec<u8>> {
+    let mut keys = Vec::new();
+    db.iterate(
+        IterateParams::new(
+            store::write::AnyKey {
+                subspace: store::SUBSPACE_CHUNKS,
+                key: vec![0u8],
+            },
+            store::write::AnyKey {
+                subspace: store::SUBSPACE_CHUNKS,
+                key: vec![u8::MAX; 10],
+            },
+        )
+        .no_values(),
+        |key, _| {
+            keys.push(key.to_vec());
+            Ok(true)
+        },
+    )
+    .await
+    .unwrap();
+    keys
 }